@@ -0,0 +1,195 @@
+use crate::errors::KVAdminerError;
+use crate::redis_ops::{client_for_address, count_keys, managed_connection, scan_keys, scan_page, KeyFilter, RedisClient, RedisConnection, RedisKey, RedisTimeouts, ScanPosition};
+use serde::Serialize;
+use log::info;
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::{ConnectionAddr, ErrorKind, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// A primary node of a Redis Cluster and the slot ranges it serves.
+#[derive(Debug, Clone)]
+pub struct ClusterNode {
+    pub host: String,
+    pub port: u16,
+    pub slots: Vec<(u16, u16)>,
+}
+
+impl ClusterNode {
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyLocation {
+    pub node: String,
+    pub slot: u16,
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Data(data) => Some(String::from_utf8_lossy(data).to_string()),
+        Value::Status(status) => Some(status.clone()),
+        Value::Int(int) => Some(int.to_string()),
+        _ => None,
+    }
+}
+
+fn value_to_u16(value: &Value) -> Option<u16> {
+    value_to_string(value)?.parse().ok()
+}
+
+/// Reads the primaries and their slot ranges from `CLUSTER SLOTS`.
+///
+/// Each entry of the reply is `[start, end, [host, port, id, ...], replicas...]`. An empty host
+/// means "the node that answered", in which case `seed_host` is used.
+pub async fn get_primaries<C: ConnectionLike + Send>(con: &mut C, seed_host: &str) -> Result<Vec<ClusterNode>, KVAdminerError> {
+    let reply: Value = redis::cmd("CLUSTER").arg("SLOTS").query_async(con).await?;
+    let ranges = match reply {
+        Value::Bulk(ranges) => ranges,
+        _ => return Err(redis::RedisError::from((ErrorKind::TypeError, "Unexpected CLUSTER SLOTS reply")).into()),
+    };
+
+    let mut nodes: Vec<ClusterNode> = vec![];
+    for range in ranges {
+        let Value::Bulk(items) = range else { continue };
+        let (Some(start), Some(end)) = (items.first().and_then(value_to_u16), items.get(1).and_then(value_to_u16)) else {
+            continue;
+        };
+        let Some(Value::Bulk(primary)) = items.get(2) else { continue };
+        let host = match primary.first().and_then(value_to_string) {
+            Some(host) if !host.is_empty() && host != "?" => host,
+            _ => seed_host.to_string(),
+        };
+        let Some(port) = primary.get(1).and_then(value_to_u16) else { continue };
+
+        match nodes.iter_mut().find(|node| node.host == host && node.port == port) {
+            Some(node) => node.slots.push((start, end)),
+            None => nodes.push(ClusterNode { host, port, slots: vec![(start, end)] }),
+        }
+    }
+    // Stable order, so cursor based listings can refer to a node by its index
+    nodes.sort_by(|a, b| (&a.host, a.port).cmp(&(&b.host, b.port)));
+    Ok(nodes)
+}
+
+fn seed_host(seed: &redis::Client) -> String {
+    match &seed.get_connection_info().addr {
+        ConnectionAddr::Tcp(host, _) => host.clone(),
+        ConnectionAddr::TcpTls { host, .. } => host.clone(),
+        _ => String::new(),
+    }
+}
+
+/// A primary node and a connection to it.
+pub type Primary = (ClusterNode, ConnectionManager);
+
+// How long the primaries of a cluster are reused before CLUSTER SLOTS is asked again, so that
+// failovers and resharding are picked up
+const PRIMARIES_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// The primaries of a cluster and a connection to each, kept on the connection so listings neither
+/// run CLUSTER SLOTS nor connect to every node on each request.
+#[derive(Default)]
+pub struct PrimaryCache {
+    primaries: Vec<Primary>,
+    refreshed: Option<Instant>,
+}
+
+pub type ClusterPrimaries = Arc<Mutex<PrimaryCache>>;
+
+/// Returns the primaries of the cluster together with a connection to each of them, from `cache`
+/// while it is fresh. Connections to nodes that are still primaries are kept on refresh.
+pub async fn primary_connections(
+    client: &RedisClient,
+    con: &mut RedisConnection,
+    cache: &ClusterPrimaries,
+    timeouts: &RedisTimeouts,
+) -> Result<Vec<Primary>, KVAdminerError> {
+    let RedisClient::Cluster { seed, .. } = client else { return Ok(vec![]) };
+    let mut cache = cache.lock().await;
+    if cache.refreshed.is_some_and(|refreshed| refreshed.elapsed() < PRIMARIES_MAX_AGE) {
+        return Ok(cache.primaries.clone());
+    }
+
+    let nodes = get_primaries(con, &seed_host(seed)).await?;
+    info!("Discovered {} cluster primaries", nodes.len());
+    let mut primaries = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let cached = cache.primaries.iter().find(|(cached, _)| cached.host == node.host && cached.port == node.port);
+        let connection = match cached {
+            Some((_, connection)) => connection.clone(),
+            None => managed_connection(&client_for_address(seed, &node.host, node.port)?, timeouts).await?,
+        };
+        primaries.push((node, connection));
+    }
+    cache.primaries = primaries.clone();
+    cache.refreshed = Some(Instant::now());
+    Ok(primaries)
+}
+
+/// Runs `SCAN` against every primary and merges the results.
+pub async fn scan_cluster_keys(
+    primaries: &[Primary],
+    pattern: &[u8],
+    filter: &KeyFilter,
+) -> Result<Vec<RedisKey>, KVAdminerError> {
+    let mut keys = vec![];
+    for (node, con) in primaries {
+        let mut con = con.clone();
+        let batch = scan_keys(&mut con, pattern, filter).await?;
+        info!("Scanned {} keys on cluster node {}", batch.len(), node.address());
+        keys.extend(batch);
+    }
+    Ok(keys)
+}
+
+/// Fills one page of a cursor based listing, walking the primaries one after another.
+///
+/// Returns the position to continue from, `None` once every primary was scanned.
+pub async fn scan_cluster_page(
+    primaries: &[Primary],
+    mut position: ScanPosition,
+    pattern: &[u8],
+    filter: &KeyFilter,
+    count: usize,
+) -> Result<(Option<ScanPosition>, Vec<RedisKey>), KVAdminerError> {
+    let mut keys = vec![];
+    while let Some((node, con)) = primaries.get(position.node) {
+        let mut con = con.clone();
+        let (cursor, batch) = scan_page(&mut con, position.cursor, pattern, filter, count - keys.len()).await?;
+        info!("Scanned {} keys on cluster node {}", batch.len(), node.address());
+        keys.extend(batch);
+        if cursor != 0 {
+            return Ok((Some(ScanPosition { node: position.node, cursor }), keys));
+        }
+        position = ScanPosition { node: position.node + 1, cursor: 0 };
+        if keys.len() >= count {
+            break;
+        }
+    }
+    let next = (position.node < primaries.len()).then_some(position);
+    Ok((next, keys))
+}
+
+/// Sums the number of keys matching `pattern` and `filter` over all primaries.
+pub async fn count_cluster_keys(primaries: &[Primary], pattern: &[u8], filter: &KeyFilter) -> Result<u64, KVAdminerError> {
+    let mut total = 0;
+    for (_, con) in primaries {
+        let mut con = con.clone();
+        total += count_keys(&mut con, pattern, filter).await?;
+    }
+    Ok(total)
+}
+
+pub fn locate_key(primaries: &[Primary], key: &RedisKey) -> KeyLocation {
+    let slot = redis::cluster_routing::get_slot(&key.0);
+    let node = primaries
+        .iter()
+        .find(|(node, _)| node.slots.iter().any(|(start, end)| (*start..=*end).contains(&slot)))
+        .map(|(node, _)| node.address())
+        .unwrap_or_default();
+    KeyLocation { node, slot }
+}
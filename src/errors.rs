@@ -1,46 +1,214 @@
+use actix_web::http::StatusCode;
 use actix_web::{HttpResponse, ResponseError};
+use redis::ErrorKind;
+use serde::Serialize;
 use std::fmt;
 use log::error;
 
 #[derive(Debug)]
 pub enum KVAdminerError {
-    RedisError(String),
-    InvalidRedisUrl,
+    RedisError(redis::RedisError),
+    InvalidTlsConfig(String),
+    Unsupported(String),
+    InvalidInput(String),
+    NotConnected,
+    KeyNotFound(String),
+    GroupNotFound(String),
+    ProfileNotFound(String),
+    Conflict(String),
+    ProfileStorage(String),
     TypeError,
 }
 
+/// Body of every error response.
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str, // Stable machine readable error code
+    message: String,
+    redis_kind: Option<String>, // Redis error code (e.g. `WRONGTYPE`) or client error kind, for Redis errors only
+    retryable: bool, // Whether sending the same request again may succeed
+}
+
 impl fmt::Display for KVAdminerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             KVAdminerError::RedisError(err) => write!(f, "Redis Error: {}", err),
-            KVAdminerError::InvalidRedisUrl => write!(f, "Invalid Redis URL"),
-            KVAdminerError::TypeError => write!(f, "Type conversion error occurred"),
+            KVAdminerError::InvalidTlsConfig(err) => write!(f, "Invalid TLS configuration: {}", err),
+            KVAdminerError::Unsupported(err) => write!(f, "Unsupported operation: {}", err),
+            KVAdminerError::InvalidInput(err) => write!(f, "Invalid input: {}", err),
+            KVAdminerError::NotConnected => write!(f, "Not connected to Redis"),
+            KVAdminerError::KeyNotFound(key) => write!(f, "Key not found: {}", key),
+            KVAdminerError::GroupNotFound(group) => write!(f, "Consumer group not found: {}", group),
+            KVAdminerError::ProfileNotFound(name) => write!(f, "Connection profile not found: {}", name),
+            KVAdminerError::Conflict(err) => write!(f, "Conflict: {}", err),
+            KVAdminerError::ProfileStorage(err) => write!(f, "Connection profile storage error: {}", err),
+            KVAdminerError::TypeError => write!(f, "Unsupported value type"),
         }
     }
 }
 
-impl ResponseError for KVAdminerError {
-    fn error_response(&self) -> HttpResponse {
+impl std::error::Error for KVAdminerError {}
+
+impl KVAdminerError {
+    /// Machine readable code reported in the error body.
+    pub fn code(&self) -> &'static str {
+        match self {
+            KVAdminerError::RedisError(err) => match (err.kind(), err.code()) {
+                (ErrorKind::AuthenticationFailed, _) | (_, Some("NOAUTH" | "WRONGPASS")) => "auth_failed",
+                (_, Some("NOPERM")) => "permission_denied",
+                (_, Some("WRONGTYPE")) => "wrong_type",
+                (_, Some("NOGROUP")) => "group_not_found",
+                (_, Some("BUSYGROUP")) => "conflict",
+                // Commands newer than the server, e.g. ZRANGESTORE before Redis 6.2
+                (ErrorKind::ResponseError, _) if err.detail().is_some_and(|detail| detail.starts_with("unknown command")) => {
+                    "unsupported"
+                }
+                // Plain `ERR` replies are syntax and argument errors, e.g. a score that is not a float
+                (ErrorKind::ResponseError | ErrorKind::TypeError | ErrorKind::CrossSlot, _) => "invalid_input",
+                (ErrorKind::ReadOnly, _) => "read_only",
+                (ErrorKind::InvalidClientConfig, _) => "invalid_config",
+                (ErrorKind::IoError, _) if err.is_timeout() => "timeout",
+                (ErrorKind::IoError, _) => "connection_failed",
+                _ => "redis_error",
+            },
+            KVAdminerError::InvalidTlsConfig(_) => "invalid_tls_config",
+            KVAdminerError::Unsupported(_) => "unsupported",
+            KVAdminerError::InvalidInput(_) => "invalid_input",
+            KVAdminerError::NotConnected => "not_connected",
+            KVAdminerError::KeyNotFound(_) => "key_not_found",
+            KVAdminerError::GroupNotFound(_) => "group_not_found",
+            KVAdminerError::ProfileNotFound(_) => "profile_not_found",
+            KVAdminerError::Conflict(_) => "conflict",
+            KVAdminerError::ProfileStorage(_) => "storage_error",
+            KVAdminerError::TypeError => "unsupported_type",
+        }
+    }
+
+    /// Whether the same request may succeed when sent again, e.g. after a failover or reconnect.
+    pub fn retryable(&self) -> bool {
         match self {
             KVAdminerError::RedisError(err) => {
-                error!("Redis Error: {}", err);
-                HttpResponse::InternalServerError().body(err.clone())
-            }
-            KVAdminerError::InvalidRedisUrl => {
-                error!("Invalid Redis URL");
-                HttpResponse::InternalServerError().body("Invalid Redis URL")
-            }
-            KVAdminerError::TypeError => {
-                error!("Type conversion error occurred");
-                HttpResponse::InternalServerError().body("Type conversion error occurred")
+                err.is_io_error()
+                    || matches!(
+                        err.kind(),
+                        ErrorKind::BusyLoadingError | ErrorKind::TryAgain | ErrorKind::ClusterDown | ErrorKind::MasterDown
+                    )
             }
+            _ => false,
+        }
+    }
+
+    fn redis_kind(&self) -> Option<String> {
+        match self {
+            KVAdminerError::RedisError(err) => Some(err.code().map(str::to_string).unwrap_or_else(|| format!("{:?}", err.kind()))),
+            _ => None,
+        }
+    }
+}
+
+impl ResponseError for KVAdminerError {
+    fn status_code(&self) -> StatusCode {
+        match self.code() {
+            "auth_failed" | "not_connected" => StatusCode::UNAUTHORIZED,
+            "permission_denied" => StatusCode::FORBIDDEN,
+            "key_not_found" | "profile_not_found" | "group_not_found" => StatusCode::NOT_FOUND,
+            "wrong_type" | "read_only" | "conflict" => StatusCode::CONFLICT,
+            "invalid_config" | "invalid_tls_config" | "unsupported" | "invalid_input" | "unsupported_type" => StatusCode::BAD_REQUEST,
+            "timeout" => StatusCode::GATEWAY_TIMEOUT,
+            "connection_failed" => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        error!("{}", self);
+        // Storage errors can mention file paths, which are of no use to the browser
+        let message = match self {
+            KVAdminerError::ProfileStorage(_) => "Connection profile storage error".to_string(),
+            _ => self.to_string(),
+        };
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            code: self.code(),
+            message,
+            redis_kind: self.redis_kind(),
+            retryable: self.retryable(),
+        })
+    }
 }
 
 impl From<redis::RedisError> for KVAdminerError {
     fn from(err: redis::RedisError) -> KVAdminerError {
-        error!("Redis Error: {}", err);
-        KVAdminerError::RedisError(err.to_string())
+        KVAdminerError::RedisError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parses an error reply the way the client does, e.g. `WRONGTYPE Operation against a key`
+    fn server_error(reply: &str) -> KVAdminerError {
+        KVAdminerError::RedisError(redis::parse_redis_value(format!("-{}\r\n", reply).as_bytes()).unwrap_err())
+    }
+
+    fn client_error(kind: ErrorKind) -> KVAdminerError {
+        KVAdminerError::RedisError((kind, "client error").into())
+    }
+
+    #[test]
+    fn redis_errors_map_to_codes_and_statuses() {
+        let cases = [
+            (server_error("ERR value is not an integer or out of range"), "invalid_input", StatusCode::BAD_REQUEST),
+            (server_error("ERR min or max is not a float"), "invalid_input", StatusCode::BAD_REQUEST),
+            (server_error("ERR syntax error"), "invalid_input", StatusCode::BAD_REQUEST),
+            (server_error("ERR unknown command 'ZRANGESTORE', with args beginning with:"), "unsupported", StatusCode::BAD_REQUEST),
+            (server_error("CROSSSLOT Keys in request don't hash to the same slot"), "invalid_input", StatusCode::BAD_REQUEST),
+            (server_error("WRONGTYPE Operation against a key holding the wrong kind of value"), "wrong_type", StatusCode::CONFLICT),
+            (server_error("READONLY You can't write against a read only replica."), "read_only", StatusCode::CONFLICT),
+            (server_error("BUSYGROUP Consumer Group name already exists"), "conflict", StatusCode::CONFLICT),
+            (server_error("NOGROUP No such key or consumer group"), "group_not_found", StatusCode::NOT_FOUND),
+            (server_error("NOAUTH Authentication required."), "auth_failed", StatusCode::UNAUTHORIZED),
+            (server_error("WRONGPASS invalid username-password pair"), "auth_failed", StatusCode::UNAUTHORIZED),
+            (server_error("NOPERM this user has no permissions to run the 'set' command"), "permission_denied", StatusCode::FORBIDDEN),
+            (server_error("LOADING Redis is loading the dataset in memory"), "redis_error", StatusCode::INTERNAL_SERVER_ERROR),
+            (client_error(ErrorKind::TypeError), "invalid_input", StatusCode::BAD_REQUEST),
+            (client_error(ErrorKind::AuthenticationFailed), "auth_failed", StatusCode::UNAUTHORIZED),
+            (client_error(ErrorKind::InvalidClientConfig), "invalid_config", StatusCode::BAD_REQUEST),
+        ];
+        for (err, code, status) in cases {
+            assert_eq!((err.code(), err.status_code()), (code, status), "{}", err);
+        }
+    }
+
+    #[test]
+    fn io_errors_map_to_gateway_statuses() {
+        let timeout = KVAdminerError::RedisError(std::io::Error::from(std::io::ErrorKind::TimedOut).into());
+        assert_eq!((timeout.code(), timeout.status_code()), ("timeout", StatusCode::GATEWAY_TIMEOUT));
+        assert!(timeout.retryable());
+
+        let refused = KVAdminerError::RedisError(std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into());
+        assert_eq!((refused.code(), refused.status_code()), ("connection_failed", StatusCode::BAD_GATEWAY));
+        assert!(refused.retryable());
+        assert!(!server_error("ERR syntax error").retryable());
+        assert!(server_error("LOADING Redis is loading the dataset in memory").retryable());
+    }
+
+    #[test]
+    fn own_errors_map_to_statuses() {
+        let cases = [
+            (KVAdminerError::InvalidInput("bad".to_string()), StatusCode::BAD_REQUEST),
+            (KVAdminerError::InvalidTlsConfig("bad".to_string()), StatusCode::BAD_REQUEST),
+            (KVAdminerError::Unsupported("old".to_string()), StatusCode::BAD_REQUEST),
+            (KVAdminerError::TypeError, StatusCode::BAD_REQUEST),
+            (KVAdminerError::NotConnected, StatusCode::UNAUTHORIZED),
+            (KVAdminerError::KeyNotFound("k".to_string()), StatusCode::NOT_FOUND),
+            (KVAdminerError::GroupNotFound("g".to_string()), StatusCode::NOT_FOUND),
+            (KVAdminerError::ProfileNotFound("p".to_string()), StatusCode::NOT_FOUND),
+            (KVAdminerError::Conflict("exists".to_string()), StatusCode::CONFLICT),
+            (KVAdminerError::ProfileStorage("disk".to_string()), StatusCode::INTERNAL_SERVER_ERROR),
+        ];
+        for (err, status) in cases {
+            assert_eq!(err.status_code(), status, "{}", err);
+        }
     }
 }
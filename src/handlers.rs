@@ -1,299 +1,2270 @@
 use actix_files::NamedFile;
 use actix_web::{web, HttpResponse, HttpRequest, Result};
-use redis::Commands;
+use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use log::{info, error};
+use crate::cluster::{ClusterPrimaries, KeyLocation, Primary, primary_connections, scan_cluster_keys, scan_cluster_page, count_cluster_keys, locate_key};
 use crate::errors::KVAdminerError;
-use crate::redis_ops::{RedisInfo, get_redis_value, get_redis_hash, set_redis_value, set_redis_hash, RedisValueType, create_redis_client};
-use crate::session::{AppState, SessionData, get_or_create_session_id};
+use crate::key_tree::KeyTree;
+use crate::redis_ops::{HashField, RedisBytes, ScoredMember, ZRange, ZRangeBy, ZAddFlags, get_zset_range, add_zset_members, increment_zset_member, remove_zset_members, store_zset_range, SetOperation, scan_set_page, get_set_size, add_set_members, remove_set_members, set_has_member, combine_sets, store_combined_sets, ListEnd, InsertPosition, get_list_range, set_list_element, insert_list_element, push_list_elements, pop_list_elements, remove_list_elements, trim_redis_list, StreamEntry, StreamTrim, PendingSummary, PendingEntry, get_stream_range, add_redis_stream_entry, delete_redis_stream_entries, trim_redis_stream, get_stream_info, get_stream_consumers, get_pending_summary, get_pending_entries, PendingFilter, create_stream_group, destroy_stream_group, set_stream_group_id, delete_stream_consumer, claim_stream_entries, autoclaim_stream_entries, ConnectionSummary, ServerFingerprint, get_server_fingerprint, RedisInfo, RedisKey, RedisValue, Expiry, KeyFilter, TtlFilter, escape_glob, validate_glob, get_ttl, expire_key, persist_key, get_redis_value, get_value_preview, get_redis_hash, set_redis_value, set_redis_hash, create_redis_client, describe_connection, DatabaseInfo, get_keyspace_info, get_database_count, with_database, scan_keys, scan_page, count_keys, ScanPosition, RedisClient, RedisConnection, ConnectionMode};
+use crate::profiles::{ConnectionProfile, ProfileUpdate};
+use crate::session::{AppState, ConnectionData, KeyCount, KeyCounts, SessionData, get_or_create_session_id, session_tag};
+
+/// A key in a request body, given either as text (`key`) or by its id (`id`) for names that are not UTF-8.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum KeyParam {
+    Id { id: String },
+    Name { key: String },
+}
+
+impl KeyParam {
+    pub fn resolve(&self) -> Result<RedisKey, KVAdminerError> {
+        match self {
+            KeyParam::Id { id } => RedisKey::from_id(id),
+            KeyParam::Name { key } => Ok(RedisKey::from(key.clone())),
+        }
+    }
+}
 
 #[derive(Deserialize)]
 pub struct SetKeyRequest {
-    pub key: String,
+    #[serde(flatten)]
+    pub key: KeyParam,
+    #[serde(flatten)]
+    pub value: RedisValue, // `type` and `value`, in the format returned by `/get/{key}`
+    pub expiry: Option<Expiry>, // Without it the key does not expire
+}
+
+#[derive(Deserialize)]
+pub struct BulkExpireRequest {
+    pub ids: Vec<String>,
+    pub expiry: Option<Expiry>, // `None` removes the expiration instead
+}
+
+#[derive(Deserialize)]
+pub struct SetHashFieldRequest {
+    #[serde(flatten)]
+    pub key: KeyParam,
+    pub field: String,
     pub value: String,
-    pub value_type: RedisValueType,
 }
 
-#[derive(Deserialize)]
-pub struct SetHashFieldRequest {
-    pub key: String,
-    pub field: String,
-    pub value: String,
-}
+/// Range of a stream or of its pending entries; `next` of a page is passed back as `start` (or as
+/// `end` with `reverse`) to continue.
+#[derive(Deserialize)]
+pub struct StreamRangeParams {
+    pub start: Option<String>, // Entry id, `-` (the oldest entry) by default
+    pub end: Option<String>, // Entry id, `+` (the newest entry) by default
+    pub count: Option<usize>, // Defaults to `STREAM_PAGE_SIZE`
+    #[serde(default)]
+    pub reverse: bool, // Newest entries first
+}
+
+#[derive(Deserialize)]
+pub struct PendingParams {
+    pub start: Option<String>, // Entry id, `-` by default
+    pub end: Option<String>, // Entry id, `+` by default
+    pub count: Option<usize>, // Defaults to `STREAM_PAGE_SIZE`
+    pub consumer: Option<String>, // Only the entries delivered to this consumer
+    pub min_idle_ms: Option<u64>, // Only entries not delivered for at least this long (Redis 6.2+)
+}
+
+/// Resolves the `count` of a paged read: `default` when missing, at most `MAX_PAGE_SIZE`.
+fn page_size(count: Option<usize>, default: usize) -> Result<usize, KVAdminerError> {
+    match count.unwrap_or(default) {
+        0 => Err(KVAdminerError::InvalidInput("count must be at least 1".to_string())),
+        count => Ok(std::cmp::min(count, MAX_PAGE_SIZE)),
+    }
+}
+
+/// Rejects a blank consumer name, which XCLAIM would otherwise create as a new consumer.
+fn validate_consumer(consumer: &str) -> Result<(), KVAdminerError> {
+    if consumer.trim().is_empty() {
+        return Err(KVAdminerError::InvalidInput("consumer name must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct AddStreamEntryRequest {
+    pub id: Option<String>, // Explicit entry id, generated by Redis by default
+    pub fields: Vec<HashField>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteStreamEntriesRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TrimStreamRequest {
+    #[serde(flatten)]
+    pub trim: StreamTrim, // `maxlen` or `minid`
+    #[serde(default)]
+    pub approximate: bool, // Trim with `~`, letting Redis keep a few more entries
+}
+
+#[derive(Deserialize)]
+pub struct CreateGroupRequest {
+    pub group: String,
+    pub id: Option<String>, // Last delivered id to start from: `$` (new entries only, the default) or `0` (everything)
+    #[serde(default)]
+    pub mkstream: bool, // Create the stream if it does not exist
+}
+
+#[derive(Deserialize)]
+pub struct SetGroupIdRequest {
+    pub id: String, // New last delivered id, `$` for the end of the stream
+    pub entries_read: Option<u64>, // Redis 7.0+
+}
+
+#[derive(Deserialize)]
+pub struct ClaimRequest {
+    pub consumer: String, // Consumer the entries are handed over to
+    #[serde(default)]
+    pub min_idle_ms: u64, // Leave entries delivered more recently alone
+    pub ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AutoClaimRequest {
+    pub consumer: String,
+    #[serde(default)]
+    pub min_idle_ms: u64,
+    pub start: Option<String>, // Pending id to scan from, `0-0` by default
+    pub count: Option<usize>, // Defaults to `STREAM_PAGE_SIZE`
+}
+
+/// Page of a list; `next` of a page is passed back as `start` to continue.
+#[derive(Deserialize)]
+pub struct ListRangeParams {
+    pub start: Option<i64>, // Index of the first element, negative counting from the end, 0 by default
+    pub count: Option<usize>, // Defaults to `LIST_PAGE_SIZE`
+}
+
+#[derive(Deserialize)]
+pub struct SetListElementRequest {
+    pub index: i64, // Negative counting from the end
+    pub value: RedisBytes,
+}
+
+#[derive(Deserialize)]
+pub struct InsertListElementRequest {
+    pub position: InsertPosition, // `before` or `after`
+    pub pivot: RedisBytes, // Existing element the value is inserted next to
+    pub value: RedisBytes,
+}
+
+#[derive(Deserialize)]
+pub struct PushListRequest {
+    pub end: ListEnd, // `head` or `tail`
+    pub values: Vec<RedisBytes>,
+}
+
+#[derive(Deserialize)]
+pub struct PopListRequest {
+    pub end: ListEnd,
+    pub count: Option<usize>, // Defaults to 1, more needs Redis 6.2+
+}
+
+#[derive(Deserialize)]
+pub struct RemoveListElementsRequest {
+    pub value: RedisBytes,
+    #[serde(default)]
+    pub count: i64, // First `count` matches from the head, last `-count` from the tail, or all of them for 0
+}
+
+#[derive(Deserialize)]
+pub struct TrimListRequest {
+    pub start: i64,
+    pub stop: i64, // Inclusive, -1 for the last element
+}
+
+/// Page of a set; `next` of a page is passed back as `cursor` to continue.
+#[derive(Deserialize)]
+pub struct SetScanParams {
+    pub cursor: Option<u64>, // SSCAN cursor, 0 (the start) by default
+    #[serde(rename = "match")]
+    pub pattern: Option<String>, // Glob pattern the members have to match
+    pub count: Option<usize>, // Defaults to `SET_PAGE_SIZE`
+}
+
+#[derive(Deserialize)]
+pub struct SetMembersRequest {
+    pub members: Vec<RedisBytes>,
+}
+
+#[derive(Deserialize)]
+pub struct SetMemberRequest {
+    pub member: RedisBytes,
+}
+
+#[derive(Deserialize)]
+pub struct CombineSetsRequest {
+    pub operation: SetOperation, // `inter`, `union` or `diff`
+    pub ids: Vec<String>, // For `diff`, the set the others are subtracted from comes first
+    pub limit: Option<usize>, // Members returned, `SET_PREVIEW_LIMIT` by default
+}
+
+#[derive(Deserialize)]
+pub struct StoreSetsRequest {
+    pub operation: SetOperation,
+    pub ids: Vec<String>,
+    pub destination: KeyParam,
+    #[serde(default)]
+    pub overwrite: bool, // Replace the destination if it exists
+}
+
+/// Page of a sorted set; `next` of a page is passed back as `offset` to continue.
+#[derive(Deserialize)]
+pub struct ZRangeParams {
+    #[serde(default)]
+    pub by: ZRangeBy, // `rank` (the default), `score` or `lex`
+    pub min: Option<String>, // Start rank, or lowest score / lex bound; the whole set by default
+    pub max: Option<String>, // Stop rank, or highest score / lex bound
+    #[serde(default)]
+    pub reverse: bool, // Highest scores first
+    #[serde(default)]
+    pub offset: u64, // Members of the range skipped before the page starts
+    pub count: Option<usize>, // Defaults to `ZSET_PAGE_SIZE`
+}
+
+#[derive(Deserialize)]
+pub struct AddZSetMembersRequest {
+    pub members: Vec<ScoredMember>,
+    #[serde(flatten)]
+    pub flags: ZAddFlags, // `nx`, `xx`, `gt` and `lt`
+}
+
+#[derive(Deserialize)]
+pub struct IncrementZSetMemberRequest {
+    pub member: RedisBytes,
+    pub increment: f64, // Negative to decrease the score
+}
+
+#[derive(Deserialize)]
+pub struct RemoveZSetMembersRequest {
+    pub members: Vec<RedisBytes>,
+}
+
+#[derive(Deserialize)]
+pub struct StoreZRangeRequest {
+    #[serde(default)]
+    pub by: ZRangeBy,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    #[serde(default)]
+    pub reverse: bool,
+    pub offset: Option<u64>, // Score and lex ranges only
+    pub count: Option<u64>, // Score and lex ranges only, every member of the range by default
+    pub destination: KeyParam,
+    #[serde(default)]
+    pub overwrite: bool, // Replace the destination if it exists
+}
+
+#[derive(Deserialize)]
+pub struct PaginationParams {
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default)]
+    pub page_size: usize, // Required by `/keys` and capped at `MAX_PAGE_SIZE`, ignored by `/keys/count`
+    pub search: Option<String>,
+    #[serde(default)]
+    pub search_mode: SearchMode,
+    pub cursor: Option<String>, // Switches to cursor based listing; empty for the first page
+    #[serde(default)]
+    pub with_total: bool, // Cursor based listing only: also report the (approximate) number of matching keys
+    #[serde(rename = "type")]
+    pub key_type: Option<String>, // Redis type name, e.g. `hash`
+    pub ttl: Option<TtlParam>,
+    pub expires_within: Option<u64>, // Seconds; only keys expiring sooner are listed
+    pub min_size: Option<u64>, // Bytes, as reported by MEMORY USAGE
+    pub max_size: Option<u64>,
+}
+
+/// How `search` is turned into a SCAN pattern.
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    #[default]
+    Substring, // Keys containing the text, glob characters match literally
+    Prefix, // Keys starting with the text, glob characters match literally
+    Glob, // The text is a Redis glob pattern used as is
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum TtlParam {
+    Persistent,
+    Expiring,
+}
+
+impl PaginationParams {
+    fn pattern(&self) -> Result<Vec<u8>, KVAdminerError> {
+        let query = self.search.as_deref().unwrap_or_default();
+        match self.search_mode {
+            SearchMode::Substring if query.is_empty() => Ok(b"*".to_vec()),
+            SearchMode::Substring => Ok([b"*", escape_glob(query.as_bytes()).as_slice(), b"*"].concat()),
+            SearchMode::Prefix => Ok([escape_glob(query.as_bytes()).as_slice(), b"*"].concat()),
+            SearchMode::Glob if query.is_empty() => Ok(b"*".to_vec()),
+            SearchMode::Glob => {
+                validate_glob(query)?;
+                Ok(query.as_bytes().to_vec())
+            }
+        }
+    }
+
+    fn key_filter(&self) -> Result<KeyFilter, KVAdminerError> {
+        let ttl = match (self.ttl, self.expires_within) {
+            (Some(TtlParam::Persistent), Some(_)) => {
+                return Err(KVAdminerError::InvalidInput("expires_within cannot be combined with ttl=persistent".to_string()));
+            }
+            (_, Some(seconds)) => Some(TtlFilter::ExpiringWithin(seconds.saturating_mul(1000))),
+            (Some(TtlParam::Expiring), None) => Some(TtlFilter::Expiring),
+            (Some(TtlParam::Persistent), None) => Some(TtlFilter::Persistent),
+            (None, None) => None,
+        };
+        let filter = KeyFilter {
+            key_type: self.key_type.clone().filter(|key_type| !key_type.is_empty()),
+            ttl,
+            min_size: self.min_size,
+            max_size: self.max_size,
+        };
+        filter.validate()?;
+        Ok(filter)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TreeParams {
+    pub prefix: Option<String>, // Namespace to expand, e.g. `user:`; empty for the top level
+    pub prefix_id: Option<String>, // Id of the prefix instead, for prefixes that are not UTF-8
+    pub delimiter: Option<String>, // Defaults to `:`
+    pub limit: Option<usize>, // Most branches and leaves returned, defaults to `TREE_DEFAULT_LIMIT`
+}
+
+impl TreeParams {
+    fn prefix(&self) -> Result<Vec<u8>, KVAdminerError> {
+        match (&self.prefix_id, &self.prefix) {
+            (Some(id), _) => Ok(RedisKey::from_id(id)?.0),
+            (None, Some(prefix)) => Ok(prefix.as_bytes().to_vec()),
+            (None, None) => Ok(vec![]),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ConnectionParams {
+    pub connection: Option<String>, // Connection id, defaults to the session's most recent connection
+}
+
+#[derive(Deserialize)]
+pub struct ConnectRequest {
+    pub name: Option<String>, // Display name, defaults to the connection's address
+    #[serde(flatten)]
+    pub info: RedisInfo,
+}
+
+#[derive(Deserialize)]
+pub struct SelectDatabaseRequest {
+    pub db: i64,
+}
+
+#[derive(Serialize)]
+struct ConnectionEntry {
+    id: String,
+    name: String,
+    default: bool,
+    #[serde(flatten)]
+    summary: ConnectionSummary,
+}
+
+#[derive(Serialize)]
+struct ConnectionTestResult {
+    ok: bool,
+    error: Option<String>,
+    server: Option<ServerFingerprint>,
+}
+
+#[derive(Serialize)]
+struct DatabaseList {
+    current: i64,
+    node: Option<String>, // Node resolved through Sentinel, if any
+    databases: Vec<DatabaseInfo>,
+}
+
+#[derive(Serialize)]
+struct KeyDetails {
+    #[serde(flatten)]
+    value: RedisValue,
+    ttl_ms: Option<i64>, // Remaining time to live, `None` for keys without expiration
+}
+
+#[derive(Serialize)]
+struct BulkExpireResult {
+    updated: usize,
+    missing: Vec<String>, // Ids of keys that no longer exist
+}
+
+#[derive(Serialize)]
+struct StreamPage {
+    entries: Vec<StreamEntry>,
+    next: Option<String>, // Id the next page starts at, `None` at the end of the range
+}
+
+#[derive(Serialize)]
+struct StreamEntryAdded {
+    id: String,
+}
+
+#[derive(Serialize)]
+struct StreamEntriesRemoved {
+    removed: u64,
+}
+
+#[derive(Serialize)]
+struct ClaimResult {
+    claimed: Vec<String>, // Ids now pending for the target consumer
+}
+
+#[derive(Serialize)]
+struct ConsumerDeleted {
+    pending_removed: u64, // Entries the consumer still had pending, no longer pending for anyone
+}
+
+#[derive(Serialize)]
+struct StreamPending {
+    #[serde(flatten)]
+    summary: PendingSummary,
+    entries: Vec<PendingEntry>,
+    next: Option<String>, // Id the next page of pending entries starts at
+}
+
+#[derive(Serialize)]
+struct ListPage {
+    length: u64,
+    start: u64, // Absolute index of the first element
+    next: Option<u64>, // Index the next page starts at, `None` at the end of the list
+    elements: Vec<RedisBytes>,
+}
+
+#[derive(Serialize)]
+struct ListLength {
+    length: u64, // Length of the list after the change
+}
+
+#[derive(Serialize)]
+struct ListPopped {
+    elements: Vec<RedisBytes>,
+}
+
+#[derive(Serialize)]
+struct ListElementsRemoved {
+    removed: u64,
+}
+
+#[derive(Serialize)]
+struct SetPage {
+    size: u64, // Members in the whole set, matching or not
+    members: Vec<RedisBytes>,
+    next: Option<String>, // Cursor of the next page, `None` once the set was walked
+}
+
+#[derive(Serialize)]
+struct SetMembersAdded {
+    added: u64, // Members that were not in the set yet
+}
+
+#[derive(Serialize)]
+struct SetMembersRemoved {
+    removed: u64,
+}
+
+#[derive(Serialize)]
+struct SetMembership {
+    contains: bool,
+}
+
+#[derive(Serialize)]
+struct SetCombination {
+    size: u64, // Members of the whole result
+    members: Vec<RedisBytes>, // Sorted, at most `limit` of them
+}
+
+/// Key a `/sets/store` or `/zsets/{id}/store` result was written to.
+#[derive(Serialize)]
+struct StoredKey {
+    id: String,
+    key: String,
+    size: u64, // Members of the stored key
+}
+
+#[derive(Serialize)]
+struct ZSetPage {
+    size: u64, // Members in the whole sorted set
+    members: Vec<ScoredMember>,
+    next: Option<u64>, // Offset the next page starts at, `None` at the end of the range
+}
+
+#[derive(Serialize)]
+struct ZSetMembersChanged {
+    changed: u64, // Members added or given a new score
+}
+
+#[derive(Serialize)]
+struct ZSetScore {
+    score: f64,
+}
+
+#[derive(Serialize)]
+struct ZSetMembersRemoved {
+    removed: u64,
+}
+
+#[derive(Serialize)]
+struct KeyEntry {
+    id: String, // Unpadded base64url of the key name, used to address the key in URLs
+    key: String, // Display form of the name, `\xNN` escaped when it is not UTF-8
+    value: RedisValue, // Only the start of strings, lists, sorted sets and hashes
+    size: Option<u64>, // Full length of a string (bytes) or list, sorted set or hash (elements)
+    ttl_ms: Option<i64>, // Remaining time to live, `None` for keys without expiration
+    location: Option<KeyLocation>, // Owning node and hash slot, in cluster mode only
+}
+
+#[derive(Serialize)]
+struct KeyCursorPage {
+    keys: Vec<KeyEntry>,
+    mode: ConnectionMode,
+    cursor: Option<String>, // Token of the next page, `None` once every key was scanned
+    approx_total: Option<u64>, // Only with `with_total`, `None` while the keys are still being counted
+}
+
+#[derive(Serialize)]
+struct KeyTotal {
+    approx_total: Option<u64>, // `None` while the keys are still being counted
+}
+
+#[derive(Serialize)]
+struct PaginatedKeys {
+    keys: Vec<KeyEntry>,
+    mode: ConnectionMode,
+    current_page: usize,
+    total_pages: usize,
+    total_keys: usize,
+}
+
+// How long a background key count is reused before the keys are counted again
+const KEY_COUNT_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(300);
+
+const TREE_DEFAULT_LIMIT: usize = 500;
+
+// Largest `count` a paged read answers with, larger requests are cut down to it
+const MAX_PAGE_SIZE: usize = 1000;
+
+const STREAM_PAGE_SIZE: usize = 50;
+
+const LIST_PAGE_SIZE: usize = 50;
+
+const SET_PAGE_SIZE: usize = 50;
+
+const ZSET_PAGE_SIZE: usize = 50;
+
+// Members of a combined set returned by `/sets/combine` unless the request asks for another limit
+const SET_PREVIEW_LIMIT: usize = 100;
+
+// Keys grouped per tree level before the counts are reported as partial
+const TREE_MAX_KEYS: u64 = 100_000;
+
+/// Returns the client and shared async connection registered for the session through `/connect`.
+///
+/// `connection_id` picks one of the session's connections; without it the default one is used.
+async fn get_redis_connection(
+    state: web::Data<AppState>,
+    session_id: &str,
+    connection_id: Option<&str>,
+) -> Result<(RedisClient, RedisConnection), KVAdminerError> {
+    let existing = {
+        let mut connections = state.connections.lock().await;
+        connections.get_mut(session_id).and_then(|session_data| {
+            // Update last active time for session timeout
+            session_data.last_active = std::time::Instant::now();
+            let id = connection_id.or(session_data.default_connection.as_deref())?;
+            let connection_data = session_data.connections.get(id)?;
+            Some((connection_data.client.clone(), connection_data.connection.clone()))
+        })
+    };
+    let Some((client, connection)) = existing else {
+        return Err(KVAdminerError::NotConnected);
+    };
+    info!("Using existing Redis connection for session {}", session_tag(session_id));
+    // Sentinel clients hand out the connection to the node they resolved, asking the sentinels
+    // again after a failover
+    let connection = match &client {
+        RedisClient::Sentinel(_) => client.get_connection(&state.redis_timeouts).await?,
+        _ => connection,
+    };
+    Ok((client, connection))
+}
+
+fn connection_entry(id: &str, session_data: &SessionData) -> Option<ConnectionEntry> {
+    let connection_data = session_data.connections.get(id)?;
+    Some(ConnectionEntry {
+        id: id.to_string(),
+        name: connection_data.name.clone(),
+        default: session_data.default_connection.as_deref() == Some(id),
+        summary: connection_data.summary.clone(),
+    })
+}
+
+/// Checks the connection described by `info` and adds it to the session as its new default connection.
+async fn open_session(
+    state: &AppState,
+    req: &HttpRequest,
+    name: Option<String>,
+    info: &RedisInfo,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(req);
+    let summary = describe_connection(info);
+
+    // Connect without holding the sessions lock, so a slow server only delays this session
+    let client = create_redis_client(info, &state.redis_timeouts)?;
+    let mut connection = client.get_connection(&state.redis_timeouts).await?;
+    redis::cmd("PING").query_async::<_, String>(&mut connection).await?;
+
+    let connection_id = uuid::Uuid::new_v4().to_string();
+    let name = name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| summary.address.clone());
+    let entry = {
+        let mut connections = state.connections.lock().await;
+        let session_data = connections.entry(session_id.clone()).or_insert_with(|| SessionData {
+            connections: HashMap::new(),
+            default_connection: None,
+            last_active: std::time::Instant::now(),
+        });
+        session_data.connections.insert(connection_id.clone(), ConnectionData {
+            name,
+            client,
+            connection,
+            summary: summary.clone(),
+            key_counts: KeyCounts::default(),
+            primaries: ClusterPrimaries::default(),
+        });
+        session_data.default_connection = Some(connection_id.clone());
+        session_data.last_active = std::time::Instant::now();
+        connection_entry(&connection_id, session_data)
+    };
+
+    info!("Connected session {} to {} as connection {}", session_tag(&session_id), summary.address, connection_id);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(entry))
+}
+
+pub async fn connect(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    item: web::Json<ConnectRequest>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let item = item.into_inner();
+    open_session(&state, &req, item.name, &item.info).await
+}
+
+pub async fn connect_profile(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    name: web::Path<String>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let info = state.profiles.lock().await.get(&name)?.clone();
+    info!("Connecting with profile {}", name);
+    open_session(&state, &req, Some(name.into_inner()), &info).await
+}
+
+/// Connects with `info` without registering anything, and describes the server that answered.
+async fn run_connection_test(state: &AppState, info: &RedisInfo) -> ConnectionTestResult {
+    let result = async {
+        let client = create_redis_client(info, &state.redis_timeouts)?;
+        let mut connection = client.get_connection(&state.redis_timeouts).await?;
+        get_server_fingerprint(&mut connection, client.mode()).await
+    }
+    .await;
+    match result {
+        Ok(server) => {
+            info!("Connection test to {} succeeded", describe_connection(info).address);
+            ConnectionTestResult { ok: true, error: None, server: Some(server) }
+        }
+        Err(err) => {
+            error!("Connection test to {} failed: {}", describe_connection(info).address, err);
+            ConnectionTestResult { ok: false, error: Some(err.to_string()), server: None }
+        }
+    }
+}
+
+pub async fn test_connection(
+    state: web::Data<AppState>,
+    item: web::Json<ConnectRequest>,
+) -> Result<HttpResponse, KVAdminerError> {
+    Ok(HttpResponse::Ok().json(run_connection_test(&state, &item.info).await))
+}
+
+pub async fn test_profile(
+    state: web::Data<AppState>,
+    name: web::Path<String>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let info = state.profiles.lock().await.get(&name)?.clone();
+    Ok(HttpResponse::Ok().json(run_connection_test(&state, &info).await))
+}
+
+pub async fn list_profiles(state: web::Data<AppState>) -> Result<HttpResponse, KVAdminerError> {
+    let profiles = state.profiles.lock().await.list();
+    Ok(HttpResponse::Ok().json(profiles))
+}
+
+pub async fn create_profile(
+    state: web::Data<AppState>,
+    profile: web::Json<ConnectionProfile>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let profile = state.profiles.lock().await.create(profile.into_inner())?;
+    info!("Connection profile created: {}", profile.name);
+    Ok(HttpResponse::Created().json(profile))
+}
+
+pub async fn update_profile(
+    state: web::Data<AppState>,
+    name: web::Path<String>,
+    update: web::Json<ProfileUpdate>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let profile = state.profiles.lock().await.update(&name, update.into_inner())?;
+    info!("Connection profile updated: {}", profile.name);
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+pub async fn delete_profile(
+    state: web::Data<AppState>,
+    name: web::Path<String>,
+) -> Result<HttpResponse, KVAdminerError> {
+    state.profiles.lock().await.delete(&name)?;
+    info!("Connection profile deleted: {}", name);
+    Ok(HttpResponse::Ok().body("Connection profile deleted successfully"))
+}
+
+pub async fn get_connection(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let entry = {
+        let connections = state.connections.lock().await;
+        connections.get(&session_id).and_then(|session_data| {
+            let id = target.connection.as_deref().or(session_data.default_connection.as_deref())?;
+            connection_entry(id, session_data)
+        })
+    };
+    let entry = entry.ok_or(KVAdminerError::NotConnected)?;
+
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .json(entry))
+}
+
+pub async fn list_connections(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let mut entries: Vec<ConnectionEntry> = {
+        let connections = state.connections.lock().await;
+        connections
+            .get(&session_id)
+            .map(|session_data| {
+                session_data
+                    .connections
+                    .keys()
+                    .filter_map(|id| connection_entry(id, session_data))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .json(entries))
+}
+
+/// Closes the given connection, or every connection of the session when none is named.
+pub async fn disconnect(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let mut connections = state.connections.lock().await;
+    match (&target.connection, connections.get_mut(&session_id)) {
+        (Some(id), Some(session_data)) => {
+            session_data.connections.remove(id).ok_or(KVAdminerError::NotConnected)?;
+            if session_data.default_connection.as_ref() == Some(id) {
+                session_data.default_connection = session_data.connections.keys().next().cloned();
+            }
+            info!("Disconnected connection {} of session {}", id, session_tag(&session_id));
+        }
+        (Some(_), None) => return Err(KVAdminerError::NotConnected),
+        (None, _) => {
+            connections.remove(&session_id);
+            info!("Disconnected session {}", session_tag(&session_id));
+        }
+    }
+
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .body("Disconnected successfully"))
+}
+
+pub async fn get_key(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+    match get_redis_value(&mut con, &key).await.map_err(|e| {
+        error!("Error getting key from Redis: {}", e);
+        e
+    }) {
+        Ok(value) => {
+            let ttl_ms = get_ttl(&mut con, &key).await?;
+            info!("Key retrieved successfully: {}", key);
+            Ok(HttpResponse::Ok()
+                .append_header(("X-Session-ID", session_id.clone()))
+                .cookie(
+                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                        .secure(true)
+                        .http_only(true)
+                        .same_site(actix_web::cookie::SameSite::Strict)
+                        .finish()
+                )
+                .json(KeyDetails { value, ttl_ms }))
+        },
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn set_key(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    item: web::Json<SetKeyRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let key = item.key.resolve()?;
+    let result = set_redis_value(&mut con, &key, &item.value, item.expiry.as_ref()).await;
+    result.map_err(|err| {
+        error!("Failed to set key {}: {}", key, err);
+        err
+    })?;
+    info!("Key set successfully: {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Key set successfully"))
+}
+
+pub async fn get_hash(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+    match get_redis_hash(&mut con, &key).await.map_err(|e| {
+        error!("Error getting hash from Redis: {}", e);
+        e
+    }) {
+        Ok(hash) => {
+            info!("Hash retrieved successfully: {}", key);
+            Ok(HttpResponse::Ok()
+                .append_header(("X-Session-ID", session_id.clone()))
+                .cookie(
+                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                        .secure(true)
+                        .http_only(true)
+                        .same_site(actix_web::cookie::SameSite::Strict)
+                        .finish()
+                )
+                .json(hash))
+        },
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn set_hash(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    item: web::Json<SetHashFieldRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+    let key = item.key.resolve()?;
+    let result = set_redis_hash(&mut con, &key, &item.field, &item.value).await;
+    result.map_err(|err| {
+        error!("Failed to set hash field {}:{}: {}", key, item.field, err);
+        err
+    })?;
+    info!("Hash field set successfully: {}:{}", key, item.field);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Hash field set successfully"))
+}
+
+pub async fn delete_key(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+    let result: Result<i64, _> = con.del(&key).await;
+    let deleted = result.map_err(|err| {
+        error!("Failed to delete key {}: {}", key, err);
+        err
+    })?;
+    if deleted == 0 {
+        return Err(KVAdminerError::KeyNotFound(key.to_string()));
+    }
+    info!("Key deleted successfully: {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Key deleted successfully"))
+}
+
+pub async fn expire(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<Expiry>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+    if !expire_key(&mut con, &key, &item).await? {
+        return Err(KVAdminerError::KeyNotFound(key.to_string()));
+    }
+    info!("Expiration set for key: {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Expiration set successfully"))
+}
+
+pub async fn persist(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+    if !persist_key(&mut con, &key).await? {
+        return Err(KVAdminerError::KeyNotFound(key.to_string()));
+    }
+    info!("Expiration removed for key: {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Expiration removed successfully"))
+}
+
+/// Sets (or with a `null` expiry removes) the expiration of several keys.
+///
+/// Keys are updated one by one, so in cluster mode they may live on different nodes.
+pub async fn bulk_expire(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    item: web::Json<BulkExpireRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let keys = item.ids.iter().map(|id| RedisKey::from_id(id)).collect::<Result<Vec<_>, _>>()?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let mut result = BulkExpireResult { updated: 0, missing: vec![] };
+    for key in keys {
+        let updated = match &item.expiry {
+            Some(expiry) => expire_key(&mut con, &key, expiry).await?,
+            None => persist_key(&mut con, &key).await?,
+        };
+        if updated {
+            result.updated += 1;
+        } else {
+            result.missing.push(key.id());
+        }
+    }
+    info!("Expiration updated for {} keys", result.updated);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(result))
+}
+
+/// Pages through the entries of a stream with XRANGE, or XREVRANGE with `reverse`.
+pub async fn stream_entries(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    params: web::Query<StreamRangeParams>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let count = page_size(params.count, STREAM_PAGE_SIZE)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let start = params.start.as_deref().unwrap_or("-");
+    let end = params.end.as_deref().unwrap_or("+");
+    let (entries, next) = get_stream_range(&mut con, &key, start, end, count, params.reverse).await?;
+    info!("Read {} entries of stream {}", entries.len(), key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StreamPage { entries, next }))
+}
+
+pub async fn add_stream_entry(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<AddStreamEntryRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let id = add_redis_stream_entry(&mut con, &key, item.id.as_deref().unwrap_or("*"), &item.fields).await?;
+    info!("Added entry {} to stream {}", id, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StreamEntryAdded { id }))
+}
+
+pub async fn delete_stream_entries(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<DeleteStreamEntriesRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let removed = delete_redis_stream_entries(&mut con, &key, &item.ids).await?;
+    info!("Deleted {} entries of stream {}", removed, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StreamEntriesRemoved { removed }))
+}
+
+pub async fn trim_stream(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<TrimStreamRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let removed = trim_redis_stream(&mut con, &key, &item.trim, item.approximate).await?;
+    info!("Trimmed {} entries of stream {}", removed, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StreamEntriesRemoved { removed }))
+}
+
+/// Length, first and last ids and consumer groups of a stream (XINFO STREAM / XINFO GROUPS).
+pub async fn stream_info(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let info = get_stream_info(&mut con, &key).await?;
+    info!("Stream info retrieved: {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(info))
+}
+
+pub async fn stream_consumers(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let consumers = get_stream_consumers(&mut con, &key, &group).await?;
+    info!("Listed {} consumers of group {} on stream {}", consumers.len(), group, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(consumers))
+}
+
+/// The XPENDING summary of a group together with one page of its pending entries.
+pub async fn stream_pending(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    params: web::Query<PendingParams>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    let count = page_size(params.count, STREAM_PAGE_SIZE)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let summary = get_pending_summary(&mut con, &key, &group).await?;
+    let start = params.start.as_deref().unwrap_or("-");
+    let end = params.end.as_deref().unwrap_or("+");
+    let filter = PendingFilter { consumer: params.consumer.as_deref(), min_idle_ms: params.min_idle_ms };
+    let (entries, next) = get_pending_entries(&mut con, &key, &group, start, end, count, &filter).await?;
+    info!("Listed {} pending entries of group {} on stream {}", entries.len(), group, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StreamPending { summary, entries, next }))
+}
+
+pub async fn create_group(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<CreateGroupRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    if item.group.is_empty() {
+        return Err(KVAdminerError::InvalidInput("group name must not be empty".to_string()));
+    }
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    create_stream_group(&mut con, &key, &item.group, item.id.as_deref().unwrap_or("$"), item.mkstream).await?;
+    info!("Created consumer group {} on stream {}", item.group, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Consumer group created successfully"))
+}
+
+pub async fn destroy_group(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    destroy_stream_group(&mut con, &key, &group).await?;
+    info!("Destroyed consumer group {} on stream {}", group, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Consumer group destroyed successfully"))
+}
+
+pub async fn set_group_id(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    item: web::Json<SetGroupIdRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    set_stream_group_id(&mut con, &key, &group, &item.id, item.entries_read).await?;
+    info!("Set last delivered id of group {} on stream {} to {}", group, key, item.id);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("Consumer group id set successfully"))
+}
+
+pub async fn delete_consumer(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String, String)>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group, consumer) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    validate_consumer(&consumer)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let pending_removed = delete_stream_consumer(&mut con, &key, &group, &consumer).await?;
+    info!("Deleted consumer {} of group {} on stream {} with {} pending entries", consumer, group, key, pending_removed);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ConsumerDeleted { pending_removed }))
+}
+
+/// Hands the given pending entries over to another consumer (XCLAIM).
+pub async fn claim_entries(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    item: web::Json<ClaimRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    validate_consumer(&item.consumer)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let claimed = claim_stream_entries(&mut con, &key, &group, &item.consumer, item.min_idle_ms, &item.ids).await?;
+    info!("Claimed {} entries of group {} on stream {} for {}", claimed.len(), group, key, item.consumer);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ClaimResult { claimed }))
+}
+
+/// Hands pending entries idle for long enough over to another consumer, one batch at a time (XAUTOCLAIM).
+pub async fn autoclaim_entries(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    item: web::Json<AutoClaimRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let (key_id, group) = path.into_inner();
+    let key = RedisKey::from_id(&key_id)?;
+    validate_consumer(&item.consumer)?;
+    let count = page_size(item.count, STREAM_PAGE_SIZE)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let start = item.start.as_deref().unwrap_or("0-0");
+    let result = autoclaim_stream_entries(&mut con, &key, &group, &item.consumer, item.min_idle_ms, start, count).await?;
+    info!("Auto-claimed {} entries of group {} on stream {} for {}", result.claimed.len(), group, key, item.consumer);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(result))
+}
+
+/// Pages through the elements of a list with LRANGE.
+pub async fn list_elements(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    params: web::Query<ListRangeParams>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let count = page_size(params.count, LIST_PAGE_SIZE)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let (length, start, elements) = get_list_range(&mut con, &key, params.start.unwrap_or(0), count).await?;
+    let end = start + elements.len() as u64;
+    info!("Read {} elements of list {}", elements.len(), key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ListPage { length, start, next: (end < length).then_some(end), elements }))
+}
+
+/// Replaces the element at an index (LSET).
+pub async fn set_list_item(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<SetListElementRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    set_list_element(&mut con, &key, item.index, &item.value).await?;
+    info!("Set element {} of list {}", item.index, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .body("List element set successfully"))
+}
+
+/// Inserts an element before or after the first occurrence of a pivot element (LINSERT).
+pub async fn insert_list_item(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<InsertListElementRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let length = insert_list_element(&mut con, &key, item.position, &item.pivot, &item.value).await?;
+    info!("Inserted an element into list {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ListLength { length }))
+}
+
+/// Pushes elements onto the head or the tail of a list (LPUSH / RPUSH), creating it if needed.
+pub async fn push_list_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<PushListRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let length = push_list_elements(&mut con, &key, item.end, &item.values).await?;
+    info!("Pushed {} elements onto list {}", item.values.len(), key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ListLength { length }))
+}
+
+/// Removes and returns elements from the head or the tail of a list (LPOP / RPOP).
+pub async fn pop_list_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<PopListRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let count = match item.count.unwrap_or(1) {
+        0 => return Err(KVAdminerError::InvalidInput("count must be at least 1".to_string())),
+        count => count,
+    };
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let elements = pop_list_elements(&mut con, &key, item.end, count).await?;
+    info!("Popped {} elements of list {}", elements.len(), key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ListPopped { elements }))
+}
+
+/// Removes elements equal to a value (LREM).
+pub async fn remove_list_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<RemoveListElementsRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let removed = remove_list_elements(&mut con, &key, item.count, &item.value).await?;
+    info!("Removed {} elements of list {}", removed, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ListElementsRemoved { removed }))
+}
+
+/// Keeps only a range of a list (LTRIM).
+pub async fn trim_list(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<TrimListRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let length = trim_redis_list(&mut con, &key, item.start, item.stop).await?;
+    info!("Trimmed list {} to {} elements", key, length);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ListLength { length }))
+}
+
+/// Pages through the members of a set with SSCAN, optionally filtered by a glob pattern.
+pub async fn set_members(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    params: web::Query<SetScanParams>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let pattern = params.pattern.as_deref().unwrap_or("*");
+    validate_glob(pattern)?;
+    let count = page_size(params.count, SET_PAGE_SIZE)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let size = get_set_size(&mut con, &key).await?;
+    let (cursor, members) = scan_set_page(&mut con, &key, params.cursor.unwrap_or(0), pattern.as_bytes(), count).await?;
+    info!("Scanned {} members of set {}", members.len(), key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(SetPage { size, members, next: (cursor != 0).then(|| cursor.to_string()) }))
+}
+
+pub async fn add_set_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<SetMembersRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let added = add_set_members(&mut con, &key, &item.members).await?;
+    info!("Added {} members to set {}", added, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(SetMembersAdded { added }))
+}
+
+pub async fn remove_set_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<SetMembersRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let removed = remove_set_members(&mut con, &key, &item.members).await?;
+    info!("Removed {} members of set {}", removed, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(SetMembersRemoved { removed }))
+}
+
+/// Tells whether a member is in a set (SISMEMBER).
+pub async fn set_contains(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<SetMemberRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let contains = set_has_member(&mut con, &key, &item.member).await?;
+    info!("Checked membership in set {}", key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(SetMembership { contains }))
+}
+
+/// Previews the intersection, union or difference of several sets (SINTER / SUNION / SDIFF).
+pub async fn combine_set_keys(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    item: web::Json<CombineSetsRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let keys = item.ids.iter().map(|id| RedisKey::from_id(id)).collect::<Result<Vec<_>, _>>()?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let (size, members) = combine_sets(&mut con, item.operation, &keys, item.limit.unwrap_or(SET_PREVIEW_LIMIT)).await?;
+    info!("Combined {} sets into {} members", keys.len(), size);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(SetCombination { size, members }))
+}
+
+/// Stores the intersection, union or difference of several sets in a new key (SINTERSTORE /
+/// SUNIONSTORE / SDIFFSTORE).
+pub async fn store_set_keys(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    item: web::Json<StoreSetsRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let keys = item.ids.iter().map(|id| RedisKey::from_id(id)).collect::<Result<Vec<_>, _>>()?;
+    let destination = item.destination.resolve()?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let size = store_combined_sets(&mut con, item.operation, &keys, &destination, item.overwrite).await?;
+    info!("Stored {} members in set {}", size, destination);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StoredKey { id: destination.id(), key: destination.to_string(), size }))
+}
+
+/// Pages through a sorted set by rank, score or lex range, with the scores of the members.
+pub async fn zset_members(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    params: web::Query<ZRangeParams>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let range = ZRange::new(params.by, params.min.clone(), params.max.clone(), params.reverse)?;
+    let count = page_size(params.count, ZSET_PAGE_SIZE)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let (size, members, next) = get_zset_range(&mut con, &key, &range, params.offset, count).await?;
+    info!("Read {} members of sorted set {}", members.len(), key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ZSetPage { size, members, next }))
+}
+
+/// Adds members with explicit scores or updates their scores (ZADD).
+pub async fn add_zset_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<AddZSetMembersRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let changed = add_zset_members(&mut con, &key, &item.members, item.flags).await?;
+    info!("Added or updated {} members of sorted set {}", changed, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ZSetMembersChanged { changed }))
+}
+
+/// Adds to the score of a member (ZINCRBY).
+pub async fn increment_zset_item(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<IncrementZSetMemberRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let score = increment_zset_member(&mut con, &key, &item.member, item.increment).await?;
+    info!("Incremented a member of sorted set {} to {}", key, score);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ZSetScore { score }))
+}
+
+pub async fn remove_zset_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<RemoveZSetMembersRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
+
+    let removed = remove_zset_members(&mut con, &key, &item.members).await?;
+    info!("Removed {} members of sorted set {}", removed, key);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(ZSetMembersRemoved { removed }))
+}
+
+/// Copies a range of a sorted set into another key (ZRANGESTORE).
+pub async fn store_zset_items(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    key_id: web::Path<String>,
+    item: web::Json<StoreZRangeRequest>,
+    target: web::Query<ConnectionParams>,
+) -> Result<HttpResponse, KVAdminerError> {
+    let session_id = get_or_create_session_id(&req);
+    let key = RedisKey::from_id(&key_id)?;
+    let range = ZRange::new(item.by, item.min.clone(), item.max.clone(), item.reverse)?;
+    let destination = item.destination.resolve()?;
+    let (_, mut con) = get_redis_connection(state, &session_id, target.connection.as_deref()).await?;
 
-#[derive(Deserialize)]
-pub struct PaginationParams {
-    pub page: usize,
-    pub page_size: usize,
-    pub search: Option<String>,
+    let size = store_zset_range(&mut con, &key, &range, item.offset, item.count, &destination, item.overwrite).await?;
+    info!("Stored {} members of sorted set {} in {}", size, key, destination);
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(StoredKey { id: destination.id(), key: destination.to_string(), size }))
 }
 
-#[derive(Serialize)]
-struct PaginatedKeys {
-    keys: Vec<(String, String, RedisValueType)>,
-    current_page: usize,
-    total_pages: usize,
-    total_keys: usize,
+/// Reads a preview of the value (and, in cluster mode, the location) of each listed key.
+async fn key_entries(
+    client: &RedisClient,
+    con: &mut RedisConnection,
+    primaries: &[Primary],
+    keys: &[RedisKey],
+) -> Vec<KeyEntry> {
+    let mut entries = Vec::with_capacity(keys.len());
+    for key in keys {
+        let (value, size) = get_value_preview(con, key).await.unwrap_or_else(|_| (RedisValue::Unknown("N/A".to_string()), None));
+        let ttl_ms = get_ttl(con, key).await.unwrap_or(None);
+        let location = match client {
+            RedisClient::Standalone(_) | RedisClient::Sentinel(_) => None,
+            RedisClient::Cluster { .. } => Some(locate_key(primaries, key)),
+        };
+        entries.push(KeyEntry { id: key.id(), key: key.to_string(), value, size, ttl_ms, location });
+    }
+    entries
 }
 
-async fn get_redis_client(
-    state: web::Data<AppState>,
-    info: &RedisInfo,
-) -> Result<redis::Client, KVAdminerError> {
-    let session_id = info.session_id.clone().unwrap();
-    let mut connections = state.connections.lock().await;
-    if let Some(session_data) = connections.get_mut(&session_id) {
-        // Update last active time for session timeout
-        session_data.last_active = std::time::Instant::now();
-        info!("Using existing Redis client for session: {}", session_id);
-        Ok(session_data.client.clone())
-    } else {
-        let client = create_redis_client(info)?;
-        connections.insert(session_id.clone(), SessionData {
-            client: client.clone(),
-            last_active: std::time::Instant::now(),
-        });
-        info!("Created new Redis client for session: {}", session_id);
-        Ok(client)
+/// Returns the number of keys matching `pattern`, if known.
+///
+/// DBSIZE answers right away when every key matches; other patterns are counted by a background
+/// SCAN whose result is cached on the connection for `KEY_COUNT_MAX_AGE`. Only one such count runs
+/// per connection: asking for another pattern or filter aborts the one in flight.
+async fn approximate_total(
+    key_counts: KeyCounts,
+    client: &RedisClient,
+    con: &mut RedisConnection,
+    primaries: &[Primary],
+    pattern: &[u8],
+    filter: &KeyFilter,
+) -> Result<Option<u64>, KVAdminerError> {
+    if pattern == b"*" && *filter == KeyFilter::default() {
+        let total = match client {
+            RedisClient::Standalone(_) | RedisClient::Sentinel(_) => count_keys(con, pattern, filter).await?,
+            RedisClient::Cluster { .. } => count_cluster_keys(primaries, pattern, filter).await?,
+        };
+        return Ok(Some(total));
+    }
+
+    let count_key = (client.db(), pattern.to_vec(), filter.clone());
+    let mut counts = key_counts.lock().await;
+    if let Some(count) = counts.counted.get(&count_key).filter(|count| count.at.elapsed() < KEY_COUNT_MAX_AGE) {
+        return Ok(Some(count.total));
+    }
+    match counts.running.take() {
+        Some((target, task)) if target == count_key => {
+            counts.running = Some((target, task));
+            return Ok(None);
+        }
+        Some((target, task)) => {
+            info!("Aborting the count of keys matching {}", String::from_utf8_lossy(&target.1));
+            task.abort();
+        }
+        None => {}
     }
+
+    let client = client.clone();
+    let mut con = con.clone();
+    let primaries = primaries.to_vec();
+    // The task only holds a weak reference, so closing the connection drops the state and aborts it
+    let weak_counts = Arc::downgrade(&key_counts);
+    let target = count_key.clone();
+    let task = tokio::spawn(async move {
+        let result = match client {
+            RedisClient::Standalone(_) | RedisClient::Sentinel(_) => count_keys(&mut con, &target.1, &target.2).await,
+            RedisClient::Cluster { .. } => count_cluster_keys(&primaries, &target.1, &target.2).await,
+        };
+        let Some(key_counts) = weak_counts.upgrade() else { return };
+        let mut counts = key_counts.lock().await;
+        if counts.running.as_ref().is_some_and(|(running, _)| *running == target) {
+            counts.running = None;
+        }
+        match result {
+            Ok(total) => {
+                info!("Counted {} keys matching {}", total, String::from_utf8_lossy(&target.1));
+                counts.counted.insert(target, KeyCount { total, at: std::time::Instant::now() });
+            }
+            Err(err) => error!("Failed to count keys matching {}: {}", String::from_utf8_lossy(&target.1), err),
+        }
+    });
+    counts.running = Some((count_key, task.abort_handle()));
+    Ok(None)
 }
 
-pub async fn get_key(
-    state: web::Data<AppState>,
-    req: HttpRequest,
-    info: web::Query<RedisInfo>,
-    key: web::Path<String>,
-) -> Result<HttpResponse, KVAdminerError> {
-    let session_id = get_or_create_session_id(&req);
-    let client_info = RedisInfo { session_id: Some(session_id.clone()), ..info.into_inner() };
-    let client = get_redis_client(state, &client_info).await?;
-    let mut con = client.get_connection()?;
-    match get_redis_value(&mut con, &key).map_err(|e| {
-        error!("Error getting key from Redis: {}", e);
-        e
-    }) {
-        Ok((val, val_type)) => {
-            info!("Key retrieved successfully: {}", key);
-            Ok(HttpResponse::Ok()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .json((val, val_type)))
-        },
-        Err(err) => Err(err),
+/// The primaries of the cluster a request targets, cached on its connection; empty outside cluster mode.
+async fn cluster_primaries(
+    state: &AppState,
+    session_id: &str,
+    connection_id: Option<&str>,
+    client: &RedisClient,
+    con: &mut RedisConnection,
+) -> Result<Vec<Primary>, KVAdminerError> {
+    if !matches!(client, RedisClient::Cluster { .. }) {
+        return Ok(vec![]);
+    }
+    let cache = {
+        let connections = state.connections.lock().await;
+        connections.get(session_id).and_then(|session_data| {
+            let id = connection_id.or(session_data.default_connection.as_deref())?;
+            Some(session_data.connections.get(id)?.primaries.clone())
+        })
     }
+    .ok_or(KVAdminerError::NotConnected)?;
+    primary_connections(client, con, &cache, &state.redis_timeouts).await
 }
 
-pub async fn set_key(
+fn connection_key_counts(session_data: &SessionData, connection_id: Option<&str>) -> Option<KeyCounts> {
+    let id = connection_id.or(session_data.default_connection.as_deref())?;
+    Some(session_data.connections.get(id)?.key_counts.clone())
+}
+
+pub async fn list_keys(
     state: web::Data<AppState>,
     req: HttpRequest,
-    info: web::Query<RedisInfo>,
-    item: web::Json<SetKeyRequest>,
+    params: web::Query<PaginationParams>,
+    target: web::Query<ConnectionParams>,
 ) -> Result<HttpResponse, KVAdminerError> {
     let session_id = get_or_create_session_id(&req);
-    let client_info = RedisInfo { session_id: Some(session_id.clone()), ..info.into_inner() };
-    let client = get_redis_client(state, &client_info).await?;
-    let mut con = client.get_connection()?;
+    let (client, mut con) = get_redis_connection(state.clone(), &session_id, target.connection.as_deref()).await?;
+    let count = page_size(Some(params.page_size), MAX_PAGE_SIZE)?;
 
-    let result = set_redis_value(&mut con, &item.key, &item.value, &item.value_type);
-    match result {
-        Ok(_) => {
-            info!("Key set successfully: {}", item.key);
-            Ok(HttpResponse::Ok()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .body("Key set successfully"))
-        },
-        Err(_) => {
-            error!("Failed to set key: {}", item.key);
-            Ok(HttpResponse::InternalServerError()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .body("Failed to set key"))
-        },
+    let pattern = params.pattern()?;
+    let filter = params.key_filter()?;
+
+    // In cluster mode SCAN only covers the node it is sent to, so it is fanned out over all primaries
+    let primaries = cluster_primaries(&state, &session_id, target.connection.as_deref(), &client, &mut con).await?;
+
+    if let Some(token) = &params.cursor {
+        let position = ScanPosition::parse(token)?;
+        let (next, keys) = match client {
+            RedisClient::Standalone(_) | RedisClient::Sentinel(_) => {
+                let (cursor, keys) = scan_page(&mut con, position.cursor, &pattern, &filter, count).await?;
+                ((cursor != 0).then_some(ScanPosition { node: 0, cursor }), keys)
+            }
+            RedisClient::Cluster { .. } => scan_cluster_page(&primaries, position, &pattern, &filter, count).await?,
+        };
+        let key_counts = if params.with_total {
+            let connections = state.connections.lock().await;
+            connections.get(&session_id).and_then(|session_data| connection_key_counts(session_data, target.connection.as_deref()))
+        } else {
+            None
+        };
+        let approx_total = match key_counts {
+            Some(key_counts) => approximate_total(key_counts, &client, &mut con, &primaries, &pattern, &filter).await?,
+            None => None,
+        };
+        let keys = key_entries(&client, &mut con, &primaries, &keys).await;
+
+        info!("Listed {} keys from cursor {} for session {}", keys.len(), token, session_tag(&session_id));
+        return Ok(HttpResponse::Ok()
+            .append_header(("X-Session-ID", session_id.clone()))
+            .cookie(
+                actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                    .secure(true)
+                    .http_only(true)
+                    .same_site(actix_web::cookie::SameSite::Strict)
+                    .finish()
+            )
+            .json(KeyCursorPage {
+                keys,
+                mode: client.mode(),
+                cursor: next.map(|position| position.token()),
+                approx_total,
+            }));
     }
+
+    let keys = match client {
+        RedisClient::Standalone(_) | RedisClient::Sentinel(_) => scan_keys(&mut con, &pattern, &filter).await?,
+        RedisClient::Cluster { .. } => scan_cluster_keys(&primaries, &pattern, &filter).await?,
+    };
+
+    let total_keys = keys.len();
+    let total_pages = total_keys.div_ceil(count);
+
+    let start_index = std::cmp::min(params.page.saturating_mul(count), total_keys);
+    let end_index = std::cmp::min(start_index + count, total_keys);
+
+    let paginated_keys = key_entries(&client, &mut con, &primaries, &keys[start_index..end_index]).await;
+
+    info!("Listed keys for session {}", session_tag(&session_id));
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(PaginatedKeys {
+            keys: paginated_keys,
+            mode: client.mode(),
+            current_page: params.page,
+            total_pages,
+            total_keys,
+        }))
 }
 
-pub async fn get_hash(
+/// Reports the approximate number of keys matching the search and filters of `/keys`, starting a
+/// background count when it is not known yet. Meant to be polled until `approx_total` is set.
+pub async fn key_total(
     state: web::Data<AppState>,
     req: HttpRequest,
-    info: web::Query<RedisInfo>,
-    key: web::Path<String>,
+    params: web::Query<PaginationParams>,
+    target: web::Query<ConnectionParams>,
 ) -> Result<HttpResponse, KVAdminerError> {
     let session_id = get_or_create_session_id(&req);
-    let client_info = RedisInfo { session_id: Some(session_id.clone()), ..info.into_inner() };
-    let client = get_redis_client(state, &client_info).await?;
-    let mut con = client.get_connection()?;
-    match get_redis_hash(&mut con, &key).map_err(|e| {
-        error!("Error getting hash from Redis: {}", e);
-        e
-    }) {
-        Ok(hash) => {
-            info!("Hash retrieved successfully: {}", key);
-            Ok(HttpResponse::Ok()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .json(hash))
-        },
-        Err(err) => Err(err),
+    let (client, mut con) = get_redis_connection(state.clone(), &session_id, target.connection.as_deref()).await?;
+    let pattern = params.pattern()?;
+    let filter = params.key_filter()?;
+    let primaries = cluster_primaries(&state, &session_id, target.connection.as_deref(), &client, &mut con).await?;
+
+    let key_counts = {
+        let connections = state.connections.lock().await;
+        connections.get(&session_id).and_then(|session_data| connection_key_counts(session_data, target.connection.as_deref()))
     }
+    .ok_or(KVAdminerError::NotConnected)?;
+    let approx_total = approximate_total(key_counts, &client, &mut con, &primaries, &pattern, &filter).await?;
+
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(KeyTotal { approx_total }))
 }
 
-pub async fn set_hash(
+/// Lists one level of the key namespace tree: the child prefixes below `prefix` with their key
+/// counts, and the keys sitting directly at that level.
+pub async fn key_tree(
     state: web::Data<AppState>,
     req: HttpRequest,
-    info: web::Query<RedisInfo>,
-    item: web::Json<SetHashFieldRequest>,
+    params: web::Query<TreeParams>,
+    target: web::Query<ConnectionParams>,
 ) -> Result<HttpResponse, KVAdminerError> {
     let session_id = get_or_create_session_id(&req);
-    let client_info = RedisInfo { session_id: Some(session_id.clone()), ..info.into_inner() };
-    let client = get_redis_client(state, &client_info).await?;
-    let mut con = client.get_connection()?;
-    let result = set_redis_hash(&mut con, &item.key, &item.field, &item.value);
-    match result {
-        Ok(_) => {
-            info!("Hash field set successfully: {}:{}", item.key, item.field);
-            Ok(HttpResponse::Ok()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .body("Hash field set successfully"))
-        },
-        Err(_) => {
-            error!("Failed to set hash field: {}:{}", item.key, item.field);
-            Ok(HttpResponse::InternalServerError()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .body("Failed to set hash field"))
-        },
+    let prefix = params.prefix()?;
+    let delimiter = params.delimiter.clone().unwrap_or_else(|| ":".to_string());
+    if delimiter.is_empty() {
+        return Err(KVAdminerError::InvalidInput("delimiter must not be empty".to_string()));
     }
+    let limit = params.limit.unwrap_or(TREE_DEFAULT_LIMIT);
+    if limit == 0 {
+        return Err(KVAdminerError::InvalidInput("limit must be at least 1".to_string()));
+    }
+    let (client, mut con) = get_redis_connection(state.clone(), &session_id, target.connection.as_deref()).await?;
+    let primaries = cluster_primaries(&state, &session_id, target.connection.as_deref(), &client, &mut con).await?;
+
+    let pattern = [escape_glob(&prefix).as_slice(), b"*"].concat();
+    let filter = KeyFilter::default();
+    let mut tree = KeyTree::new(prefix, delimiter.into_bytes());
+    let mut position = Some(ScanPosition::default());
+    while let Some(current) = position {
+        if tree.scanned() >= TREE_MAX_KEYS {
+            break;
+        }
+        let (next, keys) = match client {
+            RedisClient::Standalone(_) | RedisClient::Sentinel(_) => {
+                let (cursor, keys) = scan_page(&mut con, current.cursor, &pattern, &filter, 1000).await?;
+                ((cursor != 0).then_some(ScanPosition { node: 0, cursor }), keys)
+            }
+            RedisClient::Cluster { .. } => scan_cluster_page(&primaries, current, &pattern, &filter, 1000).await?,
+        };
+        tree.add(keys);
+        position = next;
+    }
+    let level = tree.finish(limit, position.is_none());
+
+    info!("Listed {} branches and {} keys under prefix {} for session {}", level.branch_count, level.leaf_count, level.prefix, session_tag(&session_id));
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(level))
 }
 
-pub async fn delete_key(
+pub async fn list_databases(
     state: web::Data<AppState>,
     req: HttpRequest,
-    info: web::Query<RedisInfo>,
-    key: web::Path<String>,
+    target: web::Query<ConnectionParams>,
 ) -> Result<HttpResponse, KVAdminerError> {
     let session_id = get_or_create_session_id(&req);
-    let client_info = RedisInfo { session_id: Some(session_id.clone()), ..info.into_inner() };
-    let client = get_redis_client(state, &client_info).await?;
-    let mut con = client.get_connection()?;
-    let result: Result<(), _> = con.del(&*key);
-    match result {
-        Ok(_) => {
-            info!("Key deleted successfully: {}", key);
-            Ok(HttpResponse::Ok()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .body("Key deleted successfully"))
-        },
-        Err(_) => {
-            error!("Failed to delete key: {}", key);
-            Ok(HttpResponse::InternalServerError()
-                .append_header(("X-Session-ID", session_id.clone()))
-                .cookie(
-                    actix_web::cookie::Cookie::build("session_id", session_id.clone())
-                        .secure(true)
-                        .http_only(true)
-                        .same_site(actix_web::cookie::SameSite::Strict)
-                        .finish()
-                )
-                .body("Failed to delete key"))
-        },
-    }
+    let (client, mut con) = get_redis_connection(state.clone(), &session_id, target.connection.as_deref()).await?;
+
+    // Cluster nodes each report their own share of db0, so the keyspace is summed over all primaries
+    let keyspace = match client {
+        RedisClient::Standalone(_) | RedisClient::Sentinel(_) => get_keyspace_info(&mut con).await?,
+        RedisClient::Cluster { .. } => {
+            let mut total = DatabaseInfo { index: 0, keys: 0, expires: 0, avg_ttl: 0 };
+            for (_, mut node_con) in cluster_primaries(&state, &session_id, target.connection.as_deref(), &client, &mut con).await? {
+                for db in get_keyspace_info(&mut node_con).await? {
+                    total.keys += db.keys;
+                    total.expires += db.expires;
+                }
+            }
+            vec![total]
+        }
+    };
+    let current = client.db();
+    // Fall back to the default of 16 databases when CONFIG is unavailable
+    let highest = keyspace.iter().map(|db| db.index).chain(std::iter::once(current)).max().unwrap_or(0);
+    let count = match client {
+        RedisClient::Standalone(_) | RedisClient::Sentinel(_) => get_database_count(&mut con).await.unwrap_or_else(|| std::cmp::max(16, highest + 1)),
+        RedisClient::Cluster { .. } => 1,
+    };
+
+    let databases: Vec<DatabaseInfo> = (0..count)
+        .map(|index| {
+            keyspace
+                .iter()
+                .find(|db| db.index == index)
+                .map(|db| DatabaseInfo { index, keys: db.keys, expires: db.expires, avg_ttl: db.avg_ttl })
+                .unwrap_or(DatabaseInfo { index, keys: 0, expires: 0, avg_ttl: 0 })
+        })
+        .collect();
+    let node = match &client {
+        RedisClient::Sentinel(sentinel) => sentinel.current_address(),
+        _ => None,
+    };
+
+    info!("Listed databases for session {}", session_tag(&session_id));
+    Ok(HttpResponse::Ok()
+        .append_header(("X-Session-ID", session_id.clone()))
+        .cookie(
+            actix_web::cookie::Cookie::build("session_id", session_id.clone())
+                .secure(true)
+                .http_only(true)
+                .same_site(actix_web::cookie::SameSite::Strict)
+                .finish()
+        )
+        .json(DatabaseList { current, node, databases }))
 }
 
-pub async fn list_keys(
+pub async fn select_database(
     state: web::Data<AppState>,
     req: HttpRequest,
-    info: web::Query<RedisInfo>,
-    params: web::Query<PaginationParams>,
+    item: web::Json<SelectDatabaseRequest>,
+    target: web::Query<ConnectionParams>,
 ) -> Result<HttpResponse, KVAdminerError> {
     let session_id = get_or_create_session_id(&req);
-    let client_info = RedisInfo { session_id: Some(session_id.clone()), ..info.into_inner() };
-    let client = get_redis_client(state, &client_info).await?;
-    let mut con = client.get_connection()?;
+    let (client, _) = get_redis_connection(state.clone(), &session_id, target.connection.as_deref()).await?;
 
-    let pattern = match &params.search {
-        Some(query) => format!("*{}*", query),
-        None => "*".to_string(),
-    };
+    // Connecting selects the database, so out of range values are reported before switching
+    let new_client = with_database(&client, item.db)?;
+    let new_connection = new_client.get_connection(&state.redis_timeouts).await?;
 
-    let mut keys = vec![];
-    let mut cursor = 0;
-    loop {
-        let (new_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
-            .cursor_arg(cursor)
-            .arg("MATCH")
-            .arg(&pattern)
-            .arg("COUNT")
-            .arg(1000)
-            .query(&mut con)?;
-
-        keys.extend(batch);
-        if new_cursor == 0 {
-            break;
-        }
-        cursor = new_cursor;
+    {
+        // The connection may have been closed while the new one was being opened
+        let mut connections = state.connections.lock().await;
+        let session_data = connections.get_mut(&session_id).ok_or(KVAdminerError::NotConnected)?;
+        session_data.last_active = std::time::Instant::now();
+        let id = target.connection.clone().or(session_data.default_connection.clone());
+        let connection_data = id
+            .and_then(|id| session_data.connections.get_mut(&id))
+            .ok_or(KVAdminerError::NotConnected)?;
+        connection_data.client = new_client;
+        connection_data.connection = new_connection;
+        connection_data.summary.db = item.db;
     }
 
-    let total_keys = keys.len();
-    let total_pages = (total_keys + params.page_size - 1) / params.page_size;
-
-    let start_index = params.page * params.page_size;
-    let end_index = std::cmp::min(start_index + params.page_size, total_keys);
-
-    let paginated_keys: Vec<(String, String, RedisValueType)> = keys[start_index..end_index]
-        .iter()
-        .map(|key| {
-            let (value, val_type) = get_redis_value(&mut con, key).unwrap_or_else(|_| ("N/A".to_string(), RedisValueType::Unknown));
-            (key.clone(), value, val_type)
-        })
-        .collect();
-
-    info!("Listed keys for session: {}", session_id);
+    info!("Switched session {} to database {}", session_tag(&session_id), item.db);
     Ok(HttpResponse::Ok()
         .append_header(("X-Session-ID", session_id.clone()))
         .cookie(
@@ -303,12 +2274,7 @@ pub async fn list_keys(
                 .same_site(actix_web::cookie::SameSite::Strict)
                 .finish()
         )
-        .json(PaginatedKeys {
-            keys: paginated_keys,
-            current_page: params.page,
-            total_pages,
-            total_keys,
-        }))
+        .body("Database selected successfully"))
 }
 
 // Helper function to serve HTML files
@@ -316,3 +2282,77 @@ pub async fn serve_html(file_path: &str) -> Result<NamedFile> {
     let path: PathBuf = file_path.parse().unwrap();
     Ok(NamedFile::open(path)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::ProfileStore;
+    use crate::redis_ops::RedisTimeouts;
+    use crate::test_server::{self, Listen, TestServer};
+    use actix_web::http::StatusCode;
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::App;
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+
+    #[test]
+    fn page_size_defaults_and_caps_count() {
+        assert_eq!(page_size(None, 50).unwrap(), 50);
+        assert_eq!(page_size(Some(10), 50).unwrap(), 10);
+        assert_eq!(page_size(Some(usize::MAX), 50).unwrap(), MAX_PAGE_SIZE);
+        assert!(matches!(page_size(Some(0), 50), Err(KVAdminerError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn validate_consumer_rejects_blank_names() {
+        assert!(validate_consumer("worker-1").is_ok());
+        for consumer in ["", " ", "\t\n"] {
+            assert!(matches!(validate_consumer(consumer), Err(KVAdminerError::InvalidInput(_))), "{consumer:?}");
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs redis-server"]
+    async fn select_database_switches_the_session_connection() {
+        let dir = test_server::scratch_dir();
+        let socket = dir.join("redis.sock");
+        let _server = TestServer::start(dir.clone(), &Listen::Unix(socket.clone()), &[]);
+        let timeouts = RedisTimeouts { connect: Duration::from_secs(2), command: Duration::from_secs(2) };
+        let profiles = ProfileStore::open(dir.join("profiles.json"), &[7; 32]).unwrap();
+        let state = web::Data::new(AppState {
+            connections: Arc::new(Mutex::new(HashMap::new())),
+            session_timeout: Duration::from_secs(60),
+            redis_timeouts: timeouts,
+            profiles: Arc::new(Mutex::new(profiles)),
+        });
+        let app = init_service(
+            App::new()
+                .app_data(state.clone())
+                .route("/connect", web::post().to(connect))
+                .route("/databases/select", web::post().to(select_database)),
+        )
+        .await;
+
+        let request = TestRequest::post()
+            .uri("/connect")
+            .set_json(serde_json::json!({ "socket": socket.to_string_lossy() }))
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let session = response.response().cookies().find(|cookie| cookie.name() == "session_id").unwrap().into_owned();
+
+        let select = |db: i64| TestRequest::post().uri("/databases/select").set_json(serde_json::json!({ "db": db }));
+        let response = call_service(&app, select(3).cookie(session.clone()).to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        {
+            let connections = state.connections.lock().await;
+            let session_data = &connections[session.value()];
+            let connection_data = &session_data.connections[session_data.default_connection.as_ref().unwrap()];
+            assert_eq!(connection_data.summary.db, 3);
+            assert_eq!(connection_data.client.db(), 3);
+        }
+
+        let response = call_service(&app, select(1).to_request()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::redis_ops::RedisKey;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A namespace one level below the listed prefix, e.g. `user:123:` below `user:`.
+#[derive(Debug, Serialize)]
+pub struct TreeBranch {
+    pub name: String, // Segment between the parent prefix and the delimiter, `\xNN` escaped when it is not UTF-8
+    pub prefix_id: String, // Unpadded base64url of the full prefix, delimiter included
+    pub keys: u64, // Number of keys anywhere below the prefix
+}
+
+/// A key sitting directly at the listed level, i.e. without another delimiter after the prefix.
+#[derive(Debug, Serialize)]
+pub struct TreeLeaf {
+    pub id: String,
+    pub key: String,
+    pub name: String, // Part of the key after the prefix
+}
+
+/// One level of the namespace tree.
+#[derive(Debug, Serialize)]
+pub struct TreeLevel {
+    pub prefix: String,
+    pub prefix_id: String,
+    pub branches: Vec<TreeBranch>, // Sorted by name, at most `limit` of them
+    pub leaves: Vec<TreeLeaf>, // Sorted by name, at most `limit` of them
+    pub branch_count: usize, // Before truncation to `limit`
+    pub leaf_count: usize,
+    pub scanned: u64, // Keys below the prefix that were grouped
+    pub complete: bool, // False when the scan stopped early and the counts are partial
+}
+
+/// Groups the keys found under `prefix` by their next `delimiter` separated segment.
+pub struct KeyTree {
+    prefix: Vec<u8>,
+    delimiter: Vec<u8>,
+    branches: BTreeMap<Vec<u8>, u64>,
+    leaves: Vec<Vec<u8>>,
+    scanned: u64,
+}
+
+impl KeyTree {
+    pub fn new(prefix: Vec<u8>, delimiter: Vec<u8>) -> KeyTree {
+        KeyTree { prefix, delimiter, branches: BTreeMap::new(), leaves: vec![], scanned: 0 }
+    }
+
+    pub fn scanned(&self) -> u64 {
+        self.scanned
+    }
+
+    pub fn add(&mut self, keys: Vec<RedisKey>) {
+        for RedisKey(name) in keys {
+            // SCAN MATCH already filters on the prefix, this only guards against odd servers
+            let Some(rest) = name.strip_prefix(self.prefix.as_slice()) else {
+                continue;
+            };
+            self.scanned += 1;
+            match rest.windows(self.delimiter.len()).position(|window| window == self.delimiter) {
+                Some(end) => *self.branches.entry(rest[..end].to_vec()).or_insert(0) += 1,
+                None => self.leaves.push(rest.to_vec()),
+            }
+        }
+    }
+
+    pub fn finish(mut self, limit: usize, complete: bool) -> TreeLevel {
+        let branch_count = self.branches.len();
+        let leaf_count = self.leaves.len();
+        let branches = self
+            .branches
+            .iter()
+            .take(limit)
+            .map(|(segment, keys)| TreeBranch {
+                name: RedisKey(segment.clone()).to_string(),
+                prefix_id: RedisKey([self.prefix.as_slice(), segment, self.delimiter.as_slice()].concat()).id(),
+                keys: *keys,
+            })
+            .collect();
+        self.leaves.sort();
+        let leaves = self
+            .leaves
+            .iter()
+            .take(limit)
+            .map(|rest| {
+                let key = RedisKey([self.prefix.as_slice(), rest].concat());
+                TreeLeaf { id: key.id(), key: key.to_string(), name: RedisKey(rest.clone()).to_string() }
+            })
+            .collect();
+        let prefix = RedisKey(self.prefix);
+        TreeLevel {
+            prefix: prefix.to_string(),
+            prefix_id: prefix.id(),
+            branches,
+            leaves,
+            branch_count,
+            leaf_count,
+            scanned: self.scanned,
+            complete,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(names: &[&[u8]]) -> Vec<RedisKey> {
+        names.iter().map(|name| RedisKey(name.to_vec())).collect()
+    }
+
+    #[test]
+    fn groups_keys_by_next_segment() {
+        let mut tree = KeyTree::new(b"user:".to_vec(), b":".to_vec());
+        tree.add(keys(&[b"user:2:name", b"user:1:name", b"user:1:mail", b"user:count", b"other:1"]));
+        tree.add(keys(&[b"user:1:", b"user:admin"]));
+        assert_eq!(tree.scanned(), 6);
+
+        let level = tree.finish(100, true);
+        assert_eq!(level.prefix, "user:");
+        assert_eq!(level.prefix_id, RedisKey(b"user:".to_vec()).id());
+        let branches: Vec<_> = level.branches.iter().map(|branch| (branch.name.as_str(), branch.keys)).collect();
+        assert_eq!(branches, [("1", 3), ("2", 1)]);
+        assert_eq!(level.branches[0].prefix_id, RedisKey(b"user:1:".to_vec()).id());
+        let leaves: Vec<_> = level.leaves.iter().map(|leaf| (leaf.name.as_str(), leaf.key.as_str())).collect();
+        assert_eq!(leaves, [("admin", "user:admin"), ("count", "user:count")]);
+        assert_eq!(level.leaves[0].id, RedisKey(b"user:admin".to_vec()).id());
+        assert_eq!((level.branch_count, level.leaf_count, level.scanned, level.complete), (2, 2, 6, true));
+    }
+
+    #[test]
+    fn handles_multi_byte_delimiters_and_binary_segments() {
+        let mut tree = KeyTree::new(vec![], b"::".to_vec());
+        tree.add(keys(&[b"a::b", b"a:b", &[0xff, b':', b':', b'x']]));
+        let level = tree.finish(100, true);
+        let branches: Vec<_> = level.branches.iter().map(|branch| branch.name.as_str()).collect();
+        assert_eq!(branches, ["a", r"\xff"]);
+        assert_eq!(level.branches[1].prefix_id, RedisKey(vec![0xff, b':', b':']).id());
+        assert_eq!(level.leaves[0].name, "a:b");
+    }
+
+    #[test]
+    fn finish_truncates_to_limit_but_keeps_counts() {
+        let mut tree = KeyTree::new(vec![], b":".to_vec());
+        tree.add(keys(&[b"c:1", b"b:1", b"a:1", b"z", b"y", b"x"]));
+        let level = tree.finish(2, false);
+        let branches: Vec<_> = level.branches.iter().map(|branch| branch.name.as_str()).collect();
+        let leaves: Vec<_> = level.leaves.iter().map(|leaf| leaf.name.as_str()).collect();
+        assert_eq!((branches, leaves), (vec!["a", "b"], vec!["x", "y"]));
+        assert_eq!((level.branch_count, level.leaf_count, level.complete), (3, 3, false));
+    }
+}
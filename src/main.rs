@@ -6,22 +6,56 @@ use std::time::Duration;
 use log::info;
 use env_logger::Env;
 
+mod cluster;
 mod errors;
 mod redis_ops;
+mod sentinel;
 mod handlers;
+mod key_tree;
+mod profiles;
 mod session;
+#[cfg(test)]
+mod test_server;
 
 use handlers::*;
+use redis_ops::RedisTimeouts;
 use session::AppState;
+use profiles::ProfileStore;
+
+fn duration_from_env(name: &str, default_ms: u64) -> Duration {
+    let millis = std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default_ms);
+    Duration::from_millis(millis)
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    dotenv::dotenv().ok();
     env_logger::init_from_env(Env::default().default_filter_or("info"));
     info!("Starting the server...");
 
+    let redis_timeouts = RedisTimeouts {
+        connect: duration_from_env("REDIS_CONNECT_TIMEOUT_MS", 5000),
+        command: duration_from_env("REDIS_COMMAND_TIMEOUT_MS", 10000),
+    };
+    info!("Redis timeouts: connect {:?}, command {:?}", redis_timeouts.connect, redis_timeouts.command);
+
+    let profiles_path = std::env::var("PROFILES_FILE").unwrap_or_else(|_| "profiles.json".to_string());
+    let key_path = std::env::var("PROFILES_KEY_FILE").unwrap_or_else(|_| format!("{}.key", profiles_path));
+    let key = profiles::load_or_create_key(key_path.as_ref()).map_err(std::io::Error::other)?;
+    let mut profile_store = ProfileStore::open(profiles_path.into(), &key).map_err(std::io::Error::other)?;
+    if let Ok(config_path) = std::env::var("PROFILES_CONFIG") {
+        let config_profiles = profiles::load_config_profiles(config_path.as_ref()).map_err(std::io::Error::other)?;
+        profile_store.import(config_profiles).map_err(std::io::Error::other)?;
+    }
+
     let app_state = Arc::new(AppState {
         connections: Arc::new(Mutex::new(HashMap::new())),
         session_timeout: Duration::from_secs(3600), // 1 hour timeout
+        redis_timeouts,
+        profiles: Arc::new(Mutex::new(profile_store)),
     });
 
     let app_state_clone = app_state.clone();
@@ -45,12 +79,61 @@ async fn main() -> std::io::Result<()> {
                 "/edit-key",
                 web::get().to(|| serve_html("./static/key-edit.html")),
             )
-            .route("/get/{key}", web::get().to(get_key))
+            .route("/connect", web::post().to(connect))
+            .route("/connection", web::get().to(get_connection))
+            .route("/connection/test", web::post().to(test_connection))
+            .route("/connections", web::get().to(list_connections))
+            .route("/disconnect", web::post().to(disconnect))
+            .route("/profiles", web::get().to(list_profiles))
+            .route("/profiles", web::post().to(create_profile))
+            .route("/profiles/{name}", web::put().to(update_profile))
+            .route("/profiles/{name}", web::delete().to(delete_profile))
+            .route("/profiles/{name}/connect", web::post().to(connect_profile))
+            .route("/profiles/{name}/test", web::post().to(test_profile))
+            .route("/get/{id}", web::get().to(get_key))
             .route("/set", web::post().to(set_key))
-            .route("/delete/{key}", web::delete().to(delete_key))
+            .route("/delete/{id}", web::delete().to(delete_key))
+            .route("/expire", web::post().to(bulk_expire))
+            .route("/expire/{id}", web::post().to(expire))
+            .route("/persist/{id}", web::post().to(persist))
             .route("/keys", web::get().to(list_keys))
-            .route("/get-hash/{key}", web::get().to(get_hash))
+            .route("/keys/count", web::get().to(key_total))
+            .route("/keys/tree", web::get().to(key_tree))
+            .route("/get-hash/{id}", web::get().to(get_hash))
+            .route("/lists/{id}/elements", web::get().to(list_elements))
+            .route("/lists/{id}/set", web::post().to(set_list_item))
+            .route("/lists/{id}/insert", web::post().to(insert_list_item))
+            .route("/lists/{id}/push", web::post().to(push_list_items))
+            .route("/lists/{id}/pop", web::post().to(pop_list_items))
+            .route("/lists/{id}/remove", web::post().to(remove_list_items))
+            .route("/lists/{id}/trim", web::post().to(trim_list))
+            .route("/sets/{id}/members", web::get().to(set_members))
+            .route("/sets/{id}/add", web::post().to(add_set_items))
+            .route("/sets/{id}/remove", web::post().to(remove_set_items))
+            .route("/sets/{id}/contains", web::post().to(set_contains))
+            .route("/sets/combine", web::post().to(combine_set_keys))
+            .route("/sets/store", web::post().to(store_set_keys))
+            .route("/zsets/{id}/members", web::get().to(zset_members))
+            .route("/zsets/{id}/add", web::post().to(add_zset_items))
+            .route("/zsets/{id}/incr", web::post().to(increment_zset_item))
+            .route("/zsets/{id}/remove", web::post().to(remove_zset_items))
+            .route("/zsets/{id}/store", web::post().to(store_zset_items))
+            .route("/streams/{id}/entries", web::get().to(stream_entries))
+            .route("/streams/{id}/entries", web::post().to(add_stream_entry))
+            .route("/streams/{id}/entries/delete", web::post().to(delete_stream_entries))
+            .route("/streams/{id}/trim", web::post().to(trim_stream))
+            .route("/streams/{id}/info", web::get().to(stream_info))
+            .route("/streams/{id}/groups/{group}/consumers", web::get().to(stream_consumers))
+            .route("/streams/{id}/groups", web::post().to(create_group))
+            .route("/streams/{id}/groups/{group}", web::delete().to(destroy_group))
+            .route("/streams/{id}/groups/{group}/setid", web::post().to(set_group_id))
+            .route("/streams/{id}/groups/{group}/consumers/{consumer}", web::delete().to(delete_consumer))
+            .route("/streams/{id}/groups/{group}/pending", web::get().to(stream_pending))
+            .route("/streams/{id}/groups/{group}/claim", web::post().to(claim_entries))
+            .route("/streams/{id}/groups/{group}/autoclaim", web::post().to(autoclaim_entries))
             .route("/set-hash", web::post().to(set_hash))
+            .route("/databases", web::get().to(list_databases))
+            .route("/databases/select", web::post().to(select_database))
             .service(actix_files::Files::new("/public", "./static/public"))
     })
     .bind("0.0.0.0:8080")?
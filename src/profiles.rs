@@ -0,0 +1,394 @@
+use crate::errors::KVAdminerError;
+use crate::redis_ops::{describe_connection, ConnectionSummary, RedisInfo};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// Marks an encrypted secret in the profiles file, followed by base64(nonce || ciphertext)
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+/// A named set of connection settings that can be reused to open sessions.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub name: String,
+    #[serde(flatten)]
+    pub info: RedisInfo,
+}
+
+/// What the UI gets to see of a profile: its settings with every secret left out.
+#[derive(Serialize)]
+pub struct ProfileView {
+    pub name: String,
+    pub settings: RedisInfo,
+    pub summary: ConnectionSummary,
+    pub has_password: bool,
+    pub has_sentinel_password: bool,
+    pub has_tls_client_key: bool,
+}
+
+/// A secret of a profile that an update can remove.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSecret {
+    Password,
+    SentinelPassword,
+    TlsClientKey,
+}
+
+/// New settings of a profile, as sent to `PUT /profiles/{name}`.
+#[derive(Deserialize)]
+pub struct ProfileUpdate {
+    #[serde(flatten)]
+    pub info: RedisInfo,
+    #[serde(default)]
+    pub clear_secrets: Vec<ProfileSecret>, // Stored secrets to remove; other secrets left empty are kept
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    #[serde(default)]
+    profiles: Vec<ConnectionProfile>,
+}
+
+/// Connection profiles persisted to a JSON file, with passwords and private keys encrypted at rest.
+///
+/// Profiles are kept decrypted in memory and the whole file is rewritten on every change; a change
+/// only takes effect in memory once the file was written.
+pub struct ProfileStore {
+    path: PathBuf,
+    cipher: Aes256Gcm,
+    profiles: BTreeMap<String, RedisInfo>,
+}
+
+fn storage_error(context: &str, err: impl std::fmt::Display) -> KVAdminerError {
+    KVAdminerError::ProfileStorage(format!("{}: {}", context, err))
+}
+
+fn secret_fields(info: &mut RedisInfo) -> [&mut Option<String>; 3] {
+    [&mut info.password, &mut info.sentinel_password, &mut info.tls_client_key]
+}
+
+// In the order of `secret_fields`
+const SECRETS: [ProfileSecret; 3] = [ProfileSecret::Password, ProfileSecret::SentinelPassword, ProfileSecret::TlsClientKey];
+
+fn has_value(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| !v.is_empty())
+}
+
+/// Reads the 32 byte encryption key from `PROFILES_SECRET_KEY` (base64), or from `key_path`.
+///
+/// When neither exists a new random key is written to `key_path`, readable by the owner only.
+pub fn load_or_create_key(key_path: &Path) -> Result<Vec<u8>, KVAdminerError> {
+    if let Ok(encoded) = std::env::var("PROFILES_SECRET_KEY") {
+        return decode_key(encoded.trim(), "PROFILES_SECRET_KEY");
+    }
+    if key_path.exists() {
+        let encoded = std::fs::read_to_string(key_path).map_err(|err| storage_error("Failed to read key file", err))?;
+        return decode_key(encoded.trim(), &key_path.display().to_string());
+    }
+
+    warn!("No profiles encryption key configured, generating one at {}", key_path.display());
+    let key = Aes256Gcm::generate_key(OsRng).to_vec();
+    write_private_file(key_path, &BASE64.encode(&key))?;
+    Ok(key)
+}
+
+fn decode_key(encoded: &str, source: &str) -> Result<Vec<u8>, KVAdminerError> {
+    let key = BASE64
+        .decode(encoded)
+        .map_err(|err| storage_error(&format!("Invalid encryption key in {}", source), err))?;
+    if key.len() != 32 {
+        return Err(storage_error(&format!("Invalid encryption key in {}", source), "expected 32 bytes"));
+    }
+    Ok(key)
+}
+
+fn write_private_file(path: &Path, contents: &str) -> Result<(), KVAdminerError> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|err| storage_error(&format!("Failed to open {}", path.display()), err))?;
+    std::io::Write::write_all(&mut file, contents.as_bytes())
+        .map_err(|err| storage_error(&format!("Failed to write {}", path.display()), err))
+}
+
+/// Reads profiles from a TOML config file made of `[[profiles]]` tables.
+pub fn load_config_profiles(path: &Path) -> Result<Vec<ConnectionProfile>, KVAdminerError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| storage_error(&format!("Failed to read {}", path.display()), err))?;
+    let config: ProfilesFile = toml::from_str(&contents)
+        .map_err(|err| storage_error(&format!("Failed to parse {}", path.display()), err))?;
+    Ok(config.profiles)
+}
+
+impl ProfileStore {
+    pub fn open(path: PathBuf, key: &[u8]) -> Result<ProfileStore, KVAdminerError> {
+        let mut store = ProfileStore {
+            path,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+            profiles: BTreeMap::new(),
+        };
+        if !store.path.exists() {
+            return Ok(store);
+        }
+
+        let contents = std::fs::read_to_string(&store.path)
+            .map_err(|err| storage_error(&format!("Failed to read {}", store.path.display()), err))?;
+        let file: ProfilesFile = serde_json::from_str(&contents)
+            .map_err(|err| storage_error(&format!("Failed to parse {}", store.path.display()), err))?;
+        for mut profile in file.profiles {
+            for secret in secret_fields(&mut profile.info) {
+                if let Some(value) = secret.take() {
+                    *secret = Some(store.decrypt(&value)?);
+                }
+            }
+            store.profiles.insert(profile.name, profile.info);
+        }
+        info!("Loaded {} connection profiles from {}", store.profiles.len(), store.path.display());
+        Ok(store)
+    }
+
+    /// Adds profiles from the startup config file, replacing stored profiles with the same name.
+    pub fn import(&mut self, profiles: Vec<ConnectionProfile>) -> Result<(), KVAdminerError> {
+        let mut updated = self.profiles.clone();
+        for profile in profiles {
+            validate_name(&profile.name)?;
+            info!("Imported connection profile {} from config", profile.name);
+            updated.insert(profile.name, profile.info);
+        }
+        self.commit(updated)
+    }
+
+    pub fn list(&self) -> Vec<ProfileView> {
+        self.profiles.iter().map(|(name, info)| view(name, info)).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Result<&RedisInfo, KVAdminerError> {
+        self.profiles.get(name).ok_or_else(|| KVAdminerError::ProfileNotFound(name.to_string()))
+    }
+
+    pub fn create(&mut self, profile: ConnectionProfile) -> Result<ProfileView, KVAdminerError> {
+        validate_name(&profile.name)?;
+        if self.profiles.contains_key(&profile.name) {
+            return Err(KVAdminerError::Conflict(format!("Connection profile {} already exists", profile.name)));
+        }
+        let result = view(&profile.name, &profile.info);
+        let mut updated = self.profiles.clone();
+        updated.insert(profile.name, profile.info);
+        self.commit(updated)?;
+        Ok(result)
+    }
+
+    /// Replaces the settings of a profile. Secrets left empty keep their stored value, so the UI
+    /// never needs to read them back, unless they are listed in `clear_secrets`.
+    pub fn update(&mut self, name: &str, update: ProfileUpdate) -> Result<ProfileView, KVAdminerError> {
+        let ProfileUpdate { mut info, clear_secrets } = update;
+        let mut existing = self.get(name)?.clone();
+        for ((secret, stored), kind) in secret_fields(&mut info).into_iter().zip(secret_fields(&mut existing)).zip(SECRETS) {
+            if clear_secrets.contains(&kind) {
+                *secret = None;
+            } else if !has_value(secret) {
+                *secret = stored.take();
+            }
+        }
+        let result = view(name, &info);
+        let mut updated = self.profiles.clone();
+        updated.insert(name.to_string(), info);
+        self.commit(updated)?;
+        Ok(result)
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<(), KVAdminerError> {
+        let mut updated = self.profiles.clone();
+        updated.remove(name).ok_or_else(|| KVAdminerError::ProfileNotFound(name.to_string()))?;
+        self.commit(updated)
+    }
+
+    /// Writes `profiles` to the file and, once that succeeded, makes them the current profiles.
+    fn commit(&mut self, profiles: BTreeMap<String, RedisInfo>) -> Result<(), KVAdminerError> {
+        self.save(&profiles)?;
+        self.profiles = profiles;
+        Ok(())
+    }
+
+    fn save(&self, profiles: &BTreeMap<String, RedisInfo>) -> Result<(), KVAdminerError> {
+        let mut entries = vec![];
+        for (name, info) in profiles {
+            let mut info = info.clone();
+            for secret in secret_fields(&mut info) {
+                if let Some(value) = secret.take().filter(|v| !v.is_empty()) {
+                    *secret = Some(self.encrypt(&value)?);
+                }
+            }
+            entries.push(ConnectionProfile { name: name.clone(), info });
+        }
+        let contents = serde_json::to_string_pretty(&ProfilesFile { profiles: entries })
+            .map_err(|err| storage_error("Failed to serialize profiles", err))?;
+        write_private_file(&self.path, &contents)
+    }
+
+    fn encrypt(&self, plaintext: &str) -> Result<String, KVAdminerError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|err| storage_error("Failed to encrypt secret", err))?;
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload)))
+    }
+
+    fn decrypt(&self, value: &str) -> Result<String, KVAdminerError> {
+        let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Err(storage_error("Failed to decrypt secret", "value is not encrypted"));
+        };
+        let payload = BASE64.decode(encoded).map_err(|err| storage_error("Failed to decrypt secret", err))?;
+        if payload.len() < NONCE_LEN {
+            return Err(storage_error("Failed to decrypt secret", "value is truncated"));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| storage_error("Failed to decrypt secret", "wrong key or corrupted file"))?;
+        String::from_utf8(plaintext).map_err(|err| storage_error("Failed to decrypt secret", err))
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), KVAdminerError> {
+    if name.trim().is_empty() {
+        return Err(KVAdminerError::InvalidInput("Connection profile name must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+fn view(name: &str, info: &RedisInfo) -> ProfileView {
+    let mut settings = info.clone();
+    for secret in secret_fields(&mut settings) {
+        *secret = None;
+    }
+    ProfileView {
+        name: name.to_string(),
+        summary: describe_connection(info),
+        has_password: has_value(&info.password),
+        has_sentinel_password: has_value(&info.sentinel_password),
+        has_tls_client_key: has_value(&info.tls_client_key),
+        settings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::scratch_dir;
+
+    fn open_store(dir: &Path) -> ProfileStore {
+        ProfileStore::open(dir.join("profiles.json"), &[7; 32]).unwrap()
+    }
+
+    fn profile(name: &str, password: Option<&str>) -> ConnectionProfile {
+        ConnectionProfile {
+            name: name.to_string(),
+            info: RedisInfo {
+                host: "localhost".to_string(),
+                port: "6379".to_string(),
+                password: password.map(str::to_string),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn secrets_round_trip_through_encryption() {
+        let dir = scratch_dir();
+        let store = open_store(&dir);
+        let encrypted = store.encrypt("p@ss:w/rd").unwrap();
+        assert!(encrypted.starts_with(ENCRYPTED_PREFIX));
+        assert!(!encrypted.contains("p@ss"));
+        assert_ne!(encrypted, store.encrypt("p@ss:w/rd").unwrap(), "every secret gets a fresh nonce");
+        assert_eq!(store.decrypt(&encrypted).unwrap(), "p@ss:w/rd");
+
+        let other = ProfileStore::open(dir.join("other.json"), &[8; 32]).unwrap();
+        assert!(other.decrypt(&encrypted).is_err());
+        assert!(store.decrypt("p@ss:w/rd").is_err());
+        assert!(store.decrypt(&format!("{}AAAA", ENCRYPTED_PREFIX)).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saved_profiles_keep_secrets_encrypted_on_disk() {
+        let dir = scratch_dir();
+        open_store(&dir).create(profile("prod", Some("hunter2"))).unwrap();
+        let contents = std::fs::read_to_string(dir.join("profiles.json")).unwrap();
+        assert!(!contents.contains("hunter2"));
+        assert!(contents.contains(ENCRYPTED_PREFIX));
+
+        let reopened = open_store(&dir);
+        assert_eq!(reopened.get("prod").unwrap().password.as_deref(), Some("hunter2"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn update_keeps_secrets_left_empty() {
+        let dir = scratch_dir();
+        let mut store = open_store(&dir);
+        store.create(profile("prod", Some("hunter2"))).unwrap();
+
+        let mut changed = profile("prod", Some("")).info;
+        changed.port = "6380".to_string();
+        let view = store.update("prod", ProfileUpdate { info: changed, clear_secrets: vec![] }).unwrap();
+        assert!(view.has_password);
+        assert!(view.settings.password.is_none());
+        assert_eq!(store.get("prod").unwrap().port, "6380");
+        assert_eq!(store.get("prod").unwrap().password.as_deref(), Some("hunter2"));
+
+        store.update("prod", ProfileUpdate { info: profile("prod", Some("swordfish")).info, clear_secrets: vec![] }).unwrap();
+        assert_eq!(open_store(&dir).get("prod").unwrap().password.as_deref(), Some("swordfish"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn update_clears_listed_secrets() {
+        let dir = scratch_dir();
+        let mut store = open_store(&dir);
+        store.create(profile("prod", Some("hunter2"))).unwrap();
+
+        let update: ProfileUpdate =
+            serde_json::from_value(serde_json::json!({"host": "localhost", "port": "6379", "clear_secrets": ["password"]})).unwrap();
+        let view = store.update("prod", update).unwrap();
+        assert!(!view.has_password);
+        assert_eq!(open_store(&dir).get("prod").unwrap().password, None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_saves_leave_profiles_unchanged() {
+        let dir = scratch_dir();
+        let mut store = open_store(&dir);
+        store.create(profile("prod", Some("hunter2"))).unwrap();
+
+        // A directory in place of the file makes every write fail
+        std::fs::remove_file(dir.join("profiles.json")).unwrap();
+        std::fs::create_dir(dir.join("profiles.json")).unwrap();
+        assert!(store.create(profile("staging", None)).is_err());
+        assert!(store.update("prod", ProfileUpdate { info: profile("prod", Some("swordfish")).info, clear_secrets: vec![] }).is_err());
+        assert!(store.delete("prod").is_err());
+
+        let names: Vec<_> = store.list().into_iter().map(|view| view.name).collect();
+        assert_eq!(names, ["prod"]);
+        assert_eq!(store.get("prod").unwrap().password.as_deref(), Some("hunter2"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
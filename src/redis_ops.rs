@@ -1,132 +1,2388 @@
 use crate::errors::KVAdminerError;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use log::info;
-use redis::{Commands};
-use std::collections::HashMap;
+use redis::{AsyncCommands, SetExpiry, SetOptions};
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::cluster::{ClusterClient, ClusterClientBuilder};
+use redis::cluster_async::ClusterConnection;
+use crate::sentinel::{SentinelClient, SentinelConnection, SentinelRole};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::time::Duration;
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionMode {
+    #[default]
+    Standalone,
+    Cluster,
+    Sentinel,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct RedisInfo {
+    #[serde(default)]
     pub host: String,
+    #[serde(default)]
     pub port: String,
     pub username: Option<String>,
     pub password: Option<String>,
-    pub session_id: Option<String>, // Optional session identifier
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub enum RedisValueType {
-    String,
-    List,
-    Set,
-    ZSet,
-    Hash,
-    None,
-    Unknown,
-}
-
-pub fn create_redis_client(info: &RedisInfo) -> Result<redis::Client, KVAdminerError> {
-    let redis_url = if let Some(username) = &info.username {
-        if let Some(password) = &info.password {
-            format!(
-                "redis://{}:{}@{}:{}/",
-                username, password, info.host, info.port
-            )
-        } else {
-            format!("redis://{}@{}:{}/", username, info.host, info.port)
+    pub mode: Option<ConnectionMode>,
+    pub socket: Option<String>, // Unix socket path, or a `unix://` / `redis+unix://` URL, used instead of host/port
+    pub db: Option<i64>, // Logical database index, defaults to 0
+    pub sentinels: Option<String>, // Comma separated `host:port` list, sentinel mode only
+    pub master_name: Option<String>, // Master group name monitored by the sentinels
+    pub sentinel_password: Option<String>, // Password of the sentinels themselves, if any
+    pub read_from_replica: Option<bool>, // Browse a replica instead of the primary
+    pub tls: Option<bool>,
+    pub tls_insecure: Option<bool>, // Skip certificate verification, for test environments only
+    pub tls_ca_cert: Option<String>, // PEM encoded CA bundle
+    pub tls_client_cert: Option<String>, // PEM encoded client certificate for mutual TLS
+    pub tls_client_key: Option<String>, // PEM encoded client key for mutual TLS
+}
+
+/// What the UI shows about a connection; never contains credentials.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionSummary {
+    pub mode: ConnectionMode,
+    pub address: String,
+    pub username: Option<String>,
+    pub db: i64,
+    pub tls: bool,
+}
+
+/// What `/connection/test` reports about the server it reached.
+#[derive(Debug, Serialize)]
+pub struct ServerFingerprint {
+    pub version: Option<String>,
+    pub mode: ConnectionMode, // How kvadminer connected
+    pub server_mode: Option<String>, // What the server reports: standalone, cluster or sentinel
+    pub role: Option<String>,
+    pub protocol: i64, // RESP version spoken on the connection
+    pub modules: Vec<String>,
+    pub latency_ms: f64, // Round trip of a single PING
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseInfo {
+    pub index: i64,
+    pub keys: u64,
+    pub expires: u64,
+    pub avg_ttl: u64,
+}
+
+/// Connect and per-command timeouts applied to every connection kvadminer opens.
+#[derive(Debug, Clone, Copy)]
+pub struct RedisTimeouts {
+    pub connect: Duration,
+    pub command: Duration,
+}
+
+/// A client for either a single Redis server or a Redis Cluster.
+///
+/// Cluster clients keep the standalone client built from the seed node around, so that
+/// per-node connections (e.g. for fanning out `SCAN`) reuse its credentials and TLS settings.
+#[derive(Clone)]
+pub enum RedisClient {
+    Standalone(redis::Client),
+    Cluster { client: Box<ClusterClient>, seed: redis::Client },
+    Sentinel(Box<SentinelClient>),
+}
+
+/// A multiplexed async connection, cheap to clone and shared by all requests of a session.
+#[derive(Clone)]
+pub enum RedisConnection {
+    Standalone(Box<ConnectionManager>),
+    Sentinel(SentinelConnection),
+    Cluster(ClusterConnection),
+}
+
+/// A connection to a single server that reconnects transparently, retrying once with a short
+/// backoff before giving up.
+pub async fn managed_connection(client: &redis::Client, timeouts: &RedisTimeouts) -> Result<ConnectionManager, KVAdminerError> {
+    Ok(ConnectionManager::new_with_backoff_and_timeouts(client.clone(), 2, 100, 1, timeouts.command, timeouts.connect).await?)
+}
+
+impl RedisClient {
+    pub async fn get_connection(&self, timeouts: &RedisTimeouts) -> Result<RedisConnection, KVAdminerError> {
+        match self {
+            RedisClient::Standalone(client) => Ok(RedisConnection::Standalone(Box::new(managed_connection(client, timeouts).await?))),
+            RedisClient::Cluster { client, .. } => {
+                let connection = tokio::time::timeout(timeouts.connect, client.get_async_connection())
+                    .await
+                    .map_err(|_| {
+                        redis::RedisError::from(std::io::Error::new(std::io::ErrorKind::TimedOut, "Timed out connecting to the cluster"))
+                    })??;
+                Ok(RedisConnection::Cluster(connection))
+            }
+            RedisClient::Sentinel(client) => Ok(RedisConnection::Sentinel(client.get_connection(timeouts).await?)),
+        }
+    }
+
+    pub fn mode(&self) -> ConnectionMode {
+        match self {
+            RedisClient::Standalone(_) => ConnectionMode::Standalone,
+            RedisClient::Cluster { .. } => ConnectionMode::Cluster,
+            RedisClient::Sentinel(_) => ConnectionMode::Sentinel,
+        }
+    }
+
+    pub fn db(&self) -> i64 {
+        match self {
+            RedisClient::Standalone(client) => client.get_connection_info().redis.db,
+            RedisClient::Cluster { .. } => 0,
+            RedisClient::Sentinel(client) => client.db(),
+        }
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a redis::Cmd) -> redis::RedisFuture<'a, redis::Value> {
+        match self {
+            RedisConnection::Standalone(con) => con.req_packed_command(cmd),
+            RedisConnection::Sentinel(con) => con.req_packed_command(cmd),
+            RedisConnection::Cluster(con) => con.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> redis::RedisFuture<'a, Vec<redis::Value>> {
+        match self {
+            RedisConnection::Standalone(con) => con.req_packed_commands(cmd, offset, count),
+            RedisConnection::Sentinel(con) => con.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(con) => con.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Standalone(con) => con.get_db(),
+            RedisConnection::Sentinel(con) => con.get_db(),
+            RedisConnection::Cluster(con) => con.get_db(),
+        }
+    }
+}
+
+/// Raw bytes of a value or collection element.
+///
+/// Serialized as `{"encoding": "utf8" | "binary", "text": ..., "hex": ..., "base64": ...}`, where `text`
+/// is only set for valid UTF-8. Accepts a plain string (written as UTF-8) or `{"base64": ...}` /
+/// `{"hex": ...}` for binary data, so a value read from `/get` can be sent back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RedisBytes(pub Vec<u8>);
+
+#[derive(Serialize)]
+struct EncodedBytes<'a> {
+    encoding: &'static str,
+    text: Option<&'a str>,
+    hex: String,
+    base64: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BytesInput {
+    Text(String),
+    Encoded { base64: Option<String>, hex: Option<String> },
+}
+
+impl Serialize for RedisBytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = std::str::from_utf8(&self.0).ok();
+        EncodedBytes {
+            encoding: if text.is_some() { "utf8" } else { "binary" },
+            text,
+            hex: hex::encode(&self.0),
+            base64: BASE64.encode(&self.0),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RedisBytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        match BytesInput::deserialize(deserializer)? {
+            BytesInput::Text(text) => Ok(RedisBytes(text.into_bytes())),
+            BytesInput::Encoded { base64: Some(encoded), .. } => BASE64.decode(encoded).map(RedisBytes).map_err(D::Error::custom),
+            BytesInput::Encoded { hex: Some(encoded), .. } => hex::decode(encoded).map(RedisBytes).map_err(D::Error::custom),
+            BytesInput::Encoded { .. } => Err(D::Error::custom("expected a string, `base64` or `hex`")),
+        }
+    }
+}
+
+/// Name of a key as raw bytes.
+///
+/// Keys are addressed in URLs by their id, the unpadded base64url encoding of the name, so names that
+/// are not UTF-8 or contain `/` or `?` can be reached. `Display` shows the name as text, escaping it
+/// as `\xNN` when it is not valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RedisKey(pub Vec<u8>);
+
+impl RedisKey {
+    pub fn from_id(id: &str) -> Result<RedisKey, KVAdminerError> {
+        BASE64_URL
+            .decode(id)
+            .map(RedisKey)
+            .map_err(|_| KVAdminerError::InvalidInput(format!("invalid key id: {}", id)))
+    }
+
+    pub fn id(&self) -> String {
+        BASE64_URL.encode(&self.0)
+    }
+}
+
+impl From<String> for RedisKey {
+    fn from(name: String) -> RedisKey {
+        RedisKey(name.into_bytes())
+    }
+}
+
+impl fmt::Display for RedisKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match std::str::from_utf8(&self.0) {
+            Ok(name) => f.write_str(name),
+            Err(_) => write!(f, "{}", self.0.escape_ascii()),
+        }
+    }
+}
+
+impl redis::ToRedisArgs for RedisKey {
+    fn write_redis_args<W: ?Sized + redis::RedisWrite>(&self, out: &mut W) {
+        out.write_arg(&self.0);
+    }
+}
+
+impl redis::FromRedisValue for RedisKey {
+    fn from_redis_value(value: &redis::Value) -> redis::RedisResult<RedisKey> {
+        Vec::<u8>::from_redis_value(value).map(RedisKey)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredMember {
+    pub member: RedisBytes,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashField {
+    pub field: RedisBytes,
+    pub value: RedisBytes,
+}
+
+/// The full value of a key, serialized as `{"type": "List", "value": [...]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum RedisValue {
+    String(RedisBytes),
+    List(Vec<RedisBytes>), // In list order
+    Set(SetValue),
+    ZSet(Vec<ScoredMember>), // Ordered by score, lowest first
+    Hash(Vec<HashField>), // Ordered by field
+    Stream(StreamValue),
+    Unknown(String), // Type name of values kvadminer cannot display yet, e.g. a module type
+}
+
+/// A set as returned by `/get`: its size and a sorted preview of its members, the rest is paged
+/// through `scan_set_page`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetValue {
+    #[serde(default)]
+    pub size: u64, // Number of members in the set; on writes, when set, it must match `members`
+    pub members: Vec<RedisBytes>, // At most `SET_VALUE_MEMBERS` when read
+}
+
+/// One entry of a stream, e.g. `{"id": "1700000000000-0", "fields": [{"field": "event", "value": "login"}]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamEntry {
+    pub id: String, // `<milliseconds>-<sequence>`; `*` lets Redis generate it on writes
+    pub fields: Vec<HashField>, // In the order they were added
+}
+
+/// A stream as returned by `/get`: its length and the oldest entries, the rest is paged through
+/// `get_stream_range`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamValue {
+    #[serde(default)]
+    pub length: u64, // Number of entries in the stream; on writes, when set, it must match `entries`
+    pub entries: Vec<StreamEntry>, // At most `STREAM_VALUE_ENTRIES` when read
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.trim().is_empty())
+}
+
+/// Describes the target of a connection for display and logging, leaving out all secrets.
+pub fn describe_connection(info: &RedisInfo) -> ConnectionSummary {
+    let mode = info.mode.unwrap_or_default();
+    let address = match (mode, non_empty(&info.socket), non_empty(&info.master_name)) {
+        (ConnectionMode::Sentinel, _, Some(master_name)) => format!("{} via {}", master_name, non_empty(&info.sentinels).unwrap_or("")),
+        (ConnectionMode::Standalone, Some(socket), _) => socket_path(socket).to_string(),
+        _ => format!("{}:{}", info.host, info.port),
+    };
+    ConnectionSummary {
+        mode,
+        address,
+        username: non_empty(&info.username).map(str::to_string),
+        db: info.db.unwrap_or(0),
+        tls: info.tls.unwrap_or(false),
+    }
+}
+
+fn redis_connection_info(info: &RedisInfo) -> redis::RedisConnectionInfo {
+    redis::RedisConnectionInfo {
+        db: info.db.unwrap_or(0),
+        username: non_empty(&info.username).map(str::to_string),
+        password: non_empty(&info.password).map(str::to_string),
+    }
+}
+
+// Built directly rather than through a URL, so credentials need no percent-encoding and a
+// password without a username is still sent with AUTH
+fn build_connection_info(info: &RedisInfo, host: &str, port: &str) -> Result<redis::ConnectionInfo, KVAdminerError> {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(KVAdminerError::InvalidInput("Host is required".to_string()));
+    }
+    let port: u16 = port
+        .trim()
+        .parse()
+        .map_err(|_| KVAdminerError::InvalidInput(format!("Invalid port: {}", port)))?;
+    let addr = if info.tls.unwrap_or(false) {
+        redis::ConnectionAddr::TcpTls {
+            host: host.to_string(),
+            port,
+            insecure: info.tls_insecure.unwrap_or(false),
+            tls_params: None,
         }
     } else {
-        format!("redis://{}:{}/", info.host, info.port)
+        redis::ConnectionAddr::Tcp(host.to_string(), port)
     };
-    info!("Creating Redis client for URL: {}", redis_url);
-    redis::Client::open(redis_url).map_err(|_| KVAdminerError::InvalidRedisUrl)
+    Ok(redis::ConnectionInfo { addr, redis: redis_connection_info(info) })
 }
 
-pub fn get_redis_value(con: &mut redis::Connection, key: &str) -> Result<(String, RedisValueType), KVAdminerError> {
-    let type_cmd: redis::RedisResult<String> = redis::cmd("TYPE").arg(key).query(con);
-    match type_cmd {
-        Ok(data_type) => match data_type.as_str() {
-            "string" => {
-                let value: redis::RedisResult<String> = con.get(key);
-                value.map(|v| (v, RedisValueType::String))
-                    .map_err(|err| KVAdminerError::RedisError(err.to_string()))
-            },
-            "list" => {
-                let value: redis::RedisResult<Vec<String>> = con.lrange(key, 0, -1);
-                value.map(|v| (v.join(", "), RedisValueType::List))
-                    .map_err(|err| KVAdminerError::RedisError(err.to_string()))
-            },
-            "set" => {
-                let value: redis::RedisResult<Vec<String>> = con.smembers(key);
-                value.map(|v| (v.join(", "), RedisValueType::Set))
-                    .map_err(|err| KVAdminerError::RedisError(err.to_string()))
-            },
-            "zset" => {
-                let value: redis::RedisResult<Vec<String>> = con.zrange(key, 0, -1);
-                value.map(|v| (v.join(", "), RedisValueType::ZSet))
-                    .map_err(|err| KVAdminerError::RedisError(err.to_string()))
-            },
-            "hash" => {
-                let value: redis::RedisResult<Vec<(String, String)>> = con.hgetall(key);
-                value.map(|v| {
-                    let hash: Vec<String> = v.into_iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
-                    (hash.join(", "), RedisValueType::Hash)
-                }).map_err(|err| KVAdminerError::RedisError(err.to_string()))
-            },
-            _ => Ok((String::new(), RedisValueType::Unknown)),
+fn build_tls_certificates(info: &RedisInfo) -> Result<Option<redis::TlsCertificates>, KVAdminerError> {
+    let root_cert = non_empty(&info.tls_ca_cert).map(|pem| pem.as_bytes().to_vec());
+    let client_tls = match (non_empty(&info.tls_client_cert), non_empty(&info.tls_client_key)) {
+        (Some(cert), Some(key)) => Some(redis::ClientTlsConfig {
+            client_cert: cert.as_bytes().to_vec(),
+            client_key: key.as_bytes().to_vec(),
+        }),
+        (None, None) => None,
+        _ => {
+            return Err(KVAdminerError::InvalidTlsConfig(
+                "Client certificate and client key must be provided together".to_string(),
+            ))
+        }
+    };
+    if root_cert.is_none() && client_tls.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(redis::TlsCertificates { client_tls, root_cert }))
+}
+
+fn create_standalone_client(info: &RedisInfo, host: &str, port: &str) -> Result<redis::Client, KVAdminerError> {
+    let connection_info = build_connection_info(info, host, port)?;
+    info!(
+        "Creating Redis client for {}://{}:{}/{}",
+        if info.tls.unwrap_or(false) { "rediss" } else { "redis" },
+        host,
+        port,
+        info.db.unwrap_or(0)
+    );
+    if !info.tls.unwrap_or(false) {
+        return redis::Client::open(connection_info).map_err(KVAdminerError::from);
+    }
+    match build_tls_certificates(info)? {
+        Some(certificates) => redis::Client::build_with_tls(connection_info, certificates)
+            .map_err(|err| KVAdminerError::InvalidTlsConfig(err.to_string())),
+        None => redis::Client::open(connection_info).map_err(KVAdminerError::from),
+    }
+}
+
+fn socket_path(socket: &str) -> &str {
+    socket
+        .strip_prefix("redis+unix://")
+        .or_else(|| socket.strip_prefix("unix://"))
+        .unwrap_or(socket)
+}
+
+fn create_unix_client(info: &RedisInfo, socket: &str) -> Result<redis::Client, KVAdminerError> {
+    if info.tls.unwrap_or(false) {
+        return Err(KVAdminerError::InvalidInput("TLS is not supported over unix sockets".to_string()));
+    }
+    let path = socket_path(socket);
+    info!("Creating Redis client for unix socket: {}", path);
+    let connection_info = redis::ConnectionInfo {
+        addr: redis::ConnectionAddr::Unix(std::path::PathBuf::from(path)),
+        redis: redis_connection_info(info),
+    };
+    redis::Client::open(connection_info).map_err(KVAdminerError::from)
+}
+
+/// Builds a client for another node, keeping the template's credentials, database and TLS settings.
+pub fn client_for_address(template: &redis::Client, host: &str, port: u16) -> Result<redis::Client, KVAdminerError> {
+    let mut connection_info = template.get_connection_info().clone();
+    connection_info.addr = match connection_info.addr {
+        redis::ConnectionAddr::TcpTls { insecure, tls_params, .. } => redis::ConnectionAddr::TcpTls {
+            host: host.to_string(),
+            port,
+            insecure,
+            tls_params,
+        },
+        _ => redis::ConnectionAddr::Tcp(host.to_string(), port),
+    };
+    redis::Client::open(connection_info).map_err(KVAdminerError::from)
+}
+
+fn create_sentinel_client(info: &RedisInfo) -> Result<SentinelClient, KVAdminerError> {
+    let master_name = non_empty(&info.master_name)
+        .ok_or_else(|| KVAdminerError::InvalidInput("Sentinel mode requires a master name".to_string()))?;
+    let addresses: Vec<(&str, &str)> = non_empty(&info.sentinels)
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| address.rsplit_once(':').unwrap_or((address, "26379")))
+        .collect();
+    let (first_host, first_port) = *addresses
+        .first()
+        .ok_or_else(|| KVAdminerError::InvalidInput("Sentinel mode requires at least one sentinel address".to_string()))?;
+
+    // Sentinels share the TLS settings of the data nodes but have their own (optional) password
+    let sentinels = addresses
+        .iter()
+        .map(|(host, port)| {
+            let mut connection_info = create_standalone_client(info, host, port)?.get_connection_info().clone();
+            connection_info.redis = redis::RedisConnectionInfo {
+                db: 0,
+                username: None,
+                password: non_empty(&info.sentinel_password).map(str::to_string),
+            };
+            redis::Client::open(connection_info).map_err(KVAdminerError::from)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // The template's address is replaced with the resolved node on every lookup
+    let node_template = create_standalone_client(info, first_host, first_port)?;
+    let role = if info.read_from_replica.unwrap_or(false) { SentinelRole::Replica } else { SentinelRole::Primary };
+
+    Ok(SentinelClient::new(sentinels, master_name.to_string(), role, node_template))
+}
+
+pub fn create_redis_client(info: &RedisInfo, timeouts: &RedisTimeouts) -> Result<RedisClient, KVAdminerError> {
+    match info.mode.unwrap_or_default() {
+        ConnectionMode::Standalone => match non_empty(&info.socket) {
+            Some(socket) => Ok(RedisClient::Standalone(create_unix_client(info, socket)?)),
+            None => Ok(RedisClient::Standalone(create_standalone_client(info, &info.host, &info.port)?)),
         },
-        Err(err) => {
-            log::error!("Redis Error: {}", err);
-            Err(KVAdminerError::RedisError(err.to_string()))
+        ConnectionMode::Sentinel => Ok(RedisClient::Sentinel(Box::new(create_sentinel_client(info)?))),
+        ConnectionMode::Cluster => {
+            if non_empty(&info.socket).is_some() {
+                return Err(KVAdminerError::Unsupported("Redis Cluster cannot be reached over a unix socket".to_string()));
+            }
+            let client = create_standalone_client(info, &info.host, &info.port)?;
+            if info.db.unwrap_or(0) != 0 {
+                return Err(KVAdminerError::Unsupported("Redis Cluster only supports database 0".to_string()));
+            }
+            let mut builder = ClusterClientBuilder::new(vec![client.get_connection_info().clone()])
+                .connection_timeout(timeouts.connect)
+                .response_timeout(timeouts.command);
+            if info.tls.unwrap_or(false) {
+                if let Some(certificates) = build_tls_certificates(info)? {
+                    builder = builder.certs(certificates);
+                }
+            }
+            let cluster = builder.build().map_err(KVAdminerError::from)?;
+            Ok(RedisClient::Cluster { client: Box::new(cluster), seed: client })
+        }
+    }
+}
+
+/// Returns a client for the same server and credentials, pointed at another logical database.
+pub fn with_database(client: &RedisClient, db: i64) -> Result<RedisClient, KVAdminerError> {
+    match client {
+        RedisClient::Standalone(client) => {
+            let mut connection_info = client.get_connection_info().clone();
+            connection_info.redis.db = db;
+            let client = redis::Client::open(connection_info).map_err(KVAdminerError::from)?;
+            Ok(RedisClient::Standalone(client))
+        }
+        RedisClient::Cluster { .. } if db == 0 => Ok(client.clone()),
+        RedisClient::Cluster { .. } => Err(KVAdminerError::Unsupported("Redis Cluster only supports database 0".to_string())),
+        RedisClient::Sentinel(client) => Ok(RedisClient::Sentinel(Box::new(client.with_database(db)?))),
+    }
+}
+
+/// Parses the `# Keyspace` section of `INFO`, e.g. `db3:keys=12,expires=1,avg_ttl=0`.
+pub fn parse_keyspace_info(info: &str) -> Vec<DatabaseInfo> {
+    info.lines()
+        .filter_map(|line| {
+            let (name, stats) = line.trim().split_once(':')?;
+            let index = name.strip_prefix("db")?.parse().ok()?;
+            let mut db = DatabaseInfo { index, keys: 0, expires: 0, avg_ttl: 0 };
+            for stat in stats.split(',') {
+                match stat.split_once('=') {
+                    Some(("keys", v)) => db.keys = v.parse().unwrap_or(0),
+                    Some(("expires", v)) => db.expires = v.parse().unwrap_or(0),
+                    Some(("avg_ttl", v)) => db.avg_ttl = v.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+            Some(db)
+        })
+        .collect()
+}
+
+pub async fn get_keyspace_info<C: ConnectionLike + Send>(con: &mut C) -> Result<Vec<DatabaseInfo>, KVAdminerError> {
+    let info: String = redis::cmd("INFO").arg("keyspace").query_async(con).await?;
+    Ok(parse_keyspace_info(&info))
+}
+
+/// Number of configured databases, or `None` when `CONFIG` is disabled (common on managed Redis).
+pub async fn get_database_count<C: ConnectionLike + Send>(con: &mut C) -> Option<i64> {
+    let config: redis::RedisResult<(String, String)> = redis::cmd("CONFIG").arg("GET").arg("databases").query_async(con).await;
+    config.ok().and_then(|(_, count)| count.parse().ok())
+}
+
+fn value_to_string(value: &redis::Value) -> Option<String> {
+    match value {
+        redis::Value::Data(data) => Some(String::from_utf8_lossy(data).to_string()),
+        redis::Value::Status(status) => Some(status.clone()),
+        redis::Value::Int(int) => Some(int.to_string()),
+        _ => None,
+    }
+}
+
+/// Turns a RESP2 flattened map (`[key, value, key, value, ...]`) into a lookup table.
+fn value_to_map(value: &redis::Value) -> HashMap<String, &redis::Value> {
+    match value {
+        redis::Value::Bulk(items) => items
+            .chunks(2)
+            .filter_map(|pair| match pair {
+                [key, value] => Some((value_to_string(key)?, value)),
+                _ => None,
+            })
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+fn module_names(modules: &redis::Value) -> Vec<String> {
+    match modules {
+        redis::Value::Bulk(modules) => modules
+            .iter()
+            .filter_map(|module| value_to_map(module).get("name").and_then(|name| value_to_string(name)))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn parse_info_fields(info: &str) -> HashMap<&str, &str> {
+    info.lines().filter_map(|line| line.trim().split_once(':')).collect()
+}
+
+/// Identifies the server behind a connection, using `HELLO` where available (Redis 6+) and
+/// `INFO server`, `ROLE` and `MODULE LIST` on older servers.
+pub async fn get_server_fingerprint<C: ConnectionLike + Send>(
+    con: &mut C,
+    mode: ConnectionMode,
+) -> Result<ServerFingerprint, KVAdminerError> {
+    let started = std::time::Instant::now();
+    redis::cmd("PING").query_async::<_, String>(con).await?;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let hello: redis::RedisResult<redis::Value> = redis::cmd("HELLO").query_async(con).await;
+    if let Ok(hello) = hello {
+        let fields = value_to_map(&hello);
+        return Ok(ServerFingerprint {
+            version: fields.get("version").and_then(|v| value_to_string(v)),
+            mode,
+            server_mode: fields.get("mode").and_then(|v| value_to_string(v)),
+            role: fields.get("role").and_then(|v| value_to_string(v)),
+            protocol: fields.get("proto").and_then(|v| value_to_string(v)).and_then(|v| v.parse().ok()).unwrap_or(2),
+            modules: fields.get("modules").map(|v| module_names(v)).unwrap_or_default(),
+            latency_ms,
+        });
+    }
+
+    // Before Redis 6 there is no HELLO and the connection always speaks RESP2
+    let info: String = redis::cmd("INFO").arg("server").query_async(con).await.unwrap_or_default();
+    let info = parse_info_fields(&info);
+    let role: redis::RedisResult<Vec<redis::Value>> = redis::cmd("ROLE").query_async(con).await;
+    let modules: redis::RedisResult<redis::Value> = redis::cmd("MODULE").arg("LIST").query_async(con).await;
+    Ok(ServerFingerprint {
+        version: info.get("redis_version").map(|v| v.to_string()),
+        mode,
+        server_mode: info.get("redis_mode").map(|v| v.to_string()),
+        role: role.ok().and_then(|role| role.first().and_then(value_to_string)),
+        protocol: 2,
+        modules: modules.map(|modules| module_names(&modules)).unwrap_or_default(),
+        latency_ms,
+    })
+}
+
+/// Escapes the glob metacharacters of `text`, so SCAN MATCH treats it literally.
+pub fn escape_glob(text: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(text.len());
+    for &byte in text {
+        if matches!(byte, b'*' | b'?' | b'[' | b']' | b'\\') {
+            escaped.push(b'\\');
+        }
+        escaped.push(byte);
+    }
+    escaped
+}
+
+/// Rejects glob patterns Redis would silently read differently than written: a trailing `\` or a
+/// `[` class that is never closed.
+pub fn validate_glob(pattern: &str) -> Result<(), KVAdminerError> {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // The escaped character is consumed by the guard
+            '\\' if chars.next().is_none() => {
+                return Err(KVAdminerError::InvalidInput("pattern ends with an unescaped backslash".to_string()));
+            }
+            '[' => loop {
+                match chars.next() {
+                    Some(']') => break,
+                    Some('\\') => {
+                        chars.next();
+                    }
+                    Some(_) => {}
+                    None => return Err(KVAdminerError::InvalidInput("pattern has an unclosed `[`".to_string())),
+                }
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Redis types accepted by the `type` filter of SCAN
+const KEY_TYPES: [&str; 6] = ["string", "list", "set", "zset", "hash", "stream"];
+
+/// Expiration condition of the `ttl` listing filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TtlFilter {
+    Persistent,
+    Expiring,
+    ExpiringWithin(u64), // Milliseconds
+}
+
+/// Conditions a listed key has to meet besides the SCAN pattern.
+///
+/// The type is passed to SCAN itself (Redis 6.0 or newer); TTL and size are checked afterwards with
+/// one pipelined PTTL / MEMORY USAGE round trip per SCAN batch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeyFilter {
+    pub key_type: Option<String>,
+    pub ttl: Option<TtlFilter>,
+    pub min_size: Option<u64>, // Bytes, as reported by MEMORY USAGE
+    pub max_size: Option<u64>,
+}
+
+impl KeyFilter {
+    pub fn validate(&self) -> Result<(), KVAdminerError> {
+        if let Some(key_type) = &self.key_type {
+            if !KEY_TYPES.contains(&key_type.as_str()) {
+                return Err(KVAdminerError::InvalidInput(format!("unknown key type: {}", key_type)));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_size, self.max_size) {
+            if min > max {
+                return Err(KVAdminerError::InvalidInput("min_size is larger than max_size".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn checks_size(&self) -> bool {
+        self.min_size.is_some() || self.max_size.is_some()
+    }
+
+    fn matches(&self, ttl: i64, size: Option<u64>) -> bool {
+        let ttl_matches = match self.ttl {
+            None => true,
+            Some(TtlFilter::Persistent) => ttl == -1,
+            Some(TtlFilter::Expiring) => ttl >= 0,
+            Some(TtlFilter::ExpiringWithin(millis)) => ttl >= 0 && ttl as u64 <= millis,
+        };
+        let size_matches = !self.checks_size()
+            || size.is_some_and(|size| self.min_size.is_none_or(|min| size >= min) && self.max_size.is_none_or(|max| size <= max));
+        ttl_matches && size_matches
+    }
+
+    /// Drops the keys of a SCAN batch that do not meet the TTL and size conditions.
+    async fn apply<C: ConnectionLike + Send>(&self, con: &mut C, keys: Vec<RedisKey>) -> Result<Vec<RedisKey>, KVAdminerError> {
+        if (self.ttl.is_none() && !self.checks_size()) || keys.is_empty() {
+            return Ok(keys);
+        }
+        let mut pipe = redis::pipe();
+        for key in &keys {
+            pipe.pttl(key);
+            if self.checks_size() {
+                pipe.cmd("MEMORY").arg("USAGE").arg(key);
+            }
+        }
+        let replies: Vec<redis::Value> = pipe.query_async(con).await?;
+        let width = if self.checks_size() { 2 } else { 1 };
+        let mut matching = Vec::with_capacity(keys.len());
+        for (key, reply) in keys.into_iter().zip(replies.chunks(width)) {
+            let ttl: i64 = redis::from_redis_value(&reply[0])?;
+            let size: Option<u64> = match reply.get(1) {
+                Some(value) => redis::from_redis_value(value)?,
+                None => None,
+            };
+            if self.matches(ttl, size) {
+                matching.push(key);
+            }
+        }
+        Ok(matching)
+    }
+}
+
+/// Runs one SCAN call and applies `filter` to the keys it returned.
+async fn scan_batch<C: ConnectionLike + Send>(con: &mut C, cursor: u64, pattern: &[u8], filter: &KeyFilter, count: usize) -> Result<(u64, Vec<RedisKey>), KVAdminerError> {
+    let mut cmd = redis::cmd("SCAN");
+    cmd.cursor_arg(cursor).arg("MATCH").arg(pattern).arg("COUNT").arg(count);
+    if let Some(key_type) = &filter.key_type {
+        cmd.arg("TYPE").arg(key_type);
+    }
+    let (new_cursor, batch): (u64, Vec<RedisKey>) = cmd.query_async(con).await?;
+    Ok((new_cursor, filter.apply(con, batch).await?))
+}
+
+pub async fn scan_keys<C: ConnectionLike + Send>(con: &mut C, pattern: &[u8], filter: &KeyFilter) -> Result<Vec<RedisKey>, KVAdminerError> {
+    let mut keys = vec![];
+    let mut cursor = 0;
+    loop {
+        let (new_cursor, batch) = scan_batch(con, cursor, pattern, filter, 1000).await?;
+
+        keys.extend(batch);
+        if new_cursor == 0 {
+            break;
+        }
+        cursor = new_cursor;
+    }
+    Ok(keys)
+}
+
+/// Position of a cursor based key listing, sent to the browser as `<node>:<cursor>`.
+///
+/// `node` is the index of the cluster primary being scanned (always 0 outside cluster mode) and
+/// `cursor` the SCAN cursor on that node.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScanPosition {
+    pub node: usize,
+    pub cursor: u64,
+}
+
+impl ScanPosition {
+    /// Parses a cursor token; an empty token or a bare SCAN cursor start on the first node.
+    pub fn parse(token: &str) -> Result<ScanPosition, KVAdminerError> {
+        let invalid = || KVAdminerError::InvalidInput(format!("invalid cursor: {}", token));
+        match token.split_once(':') {
+            _ if token.is_empty() => Ok(ScanPosition::default()),
+            Some((node, cursor)) => Ok(ScanPosition {
+                node: node.parse().map_err(|_| invalid())?,
+                cursor: cursor.parse().map_err(|_| invalid())?,
+            }),
+            None => Ok(ScanPosition { node: 0, cursor: token.parse().map_err(|_| invalid())? }),
+        }
+    }
+
+    pub fn token(&self) -> String {
+        format!("{}:{}", self.node, self.cursor)
+    }
+}
+
+// Upper bound of SCAN calls spent filling one page, so sparse patterns cannot stall a request
+const MAX_SCAN_CALLS_PER_PAGE: usize = 50;
+
+/// Runs SCAN from `cursor` until at least `count` keys matched, the keyspace was walked or the call
+/// budget is spent. Returns the cursor to continue from, 0 once the scan is complete.
+///
+/// A page can hold a few more keys than `count`, since SCAN batches cannot be split.
+pub async fn scan_page<C: ConnectionLike + Send>(con: &mut C, mut cursor: u64, pattern: &[u8], filter: &KeyFilter, count: usize) -> Result<(u64, Vec<RedisKey>), KVAdminerError> {
+    let mut keys = vec![];
+    // COUNT starts at the page size and grows while the pattern matches few keys
+    let mut hint = std::cmp::max(count, 1);
+    for _ in 0..MAX_SCAN_CALLS_PER_PAGE {
+        let (new_cursor, batch) = scan_batch(con, cursor, pattern, filter, hint).await?;
+
+        keys.extend(batch);
+        cursor = new_cursor;
+        if cursor == 0 || keys.len() >= count {
+            break;
+        }
+        hint = std::cmp::min(hint.saturating_mul(2), 1000);
+    }
+    Ok((cursor, keys))
+}
+
+/// Counts the keys matching `pattern` and `filter`, using DBSIZE when every key matches and a full
+/// SCAN otherwise.
+pub async fn count_keys<C: ConnectionLike + Send>(con: &mut C, pattern: &[u8], filter: &KeyFilter) -> Result<u64, KVAdminerError> {
+    if pattern == b"*" && *filter == KeyFilter::default() {
+        return Ok(redis::cmd("DBSIZE").query_async(con).await?);
+    }
+    let mut total = 0;
+    let mut cursor = 0;
+    loop {
+        let (new_cursor, batch) = scan_batch(con, cursor, pattern, filter, 1000).await?;
+
+        total += batch.len() as u64;
+        if new_cursor == 0 {
+            break;
+        }
+        cursor = new_cursor;
+    }
+    Ok(total)
+}
+
+pub async fn get_redis_value<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<RedisValue, KVAdminerError> {
+    let data_type: String = redis::cmd("TYPE").arg(key).query_async(con).await?;
+    match data_type.as_str() {
+        "string" => {
+            let value: Vec<u8> = con.get(key).await?;
+            Ok(RedisValue::String(RedisBytes(value)))
+        }
+        "list" => {
+            let values: Vec<Vec<u8>> = con.lrange(key, 0, -1).await?;
+            Ok(RedisValue::List(values.into_iter().map(RedisBytes).collect()))
+        }
+        "set" => {
+            // A single SSCAN page rather than SMEMBERS, so a huge set neither blocks the server nor
+            // ends up in memory; a page may repeat members, which the BTreeSet drops
+            let size = get_set_size(con, key).await?;
+            let (_, page) = scan_set_page(con, key, 0, b"*", SET_VALUE_MEMBERS).await?;
+            let members: BTreeSet<RedisBytes> = page.into_iter().collect();
+            Ok(RedisValue::Set(SetValue { size, members: members.into_iter().take(SET_VALUE_MEMBERS).collect() }))
+        }
+        "zset" => {
+            let members: Vec<(Vec<u8>, f64)> = con.zrange_withscores(key, 0, -1).await?;
+            Ok(RedisValue::ZSet(members.into_iter().map(|(member, score)| ScoredMember { member: RedisBytes(member), score }).collect()))
+        }
+        "hash" => {
+            let mut fields: Vec<(Vec<u8>, Vec<u8>)> = con.hgetall(key).await?;
+            fields.sort();
+            Ok(RedisValue::Hash(fields.into_iter().map(|(field, value)| HashField { field: RedisBytes(field), value: RedisBytes(value) }).collect()))
+        }
+        "stream" => {
+            let length: u64 = redis::cmd("XLEN").arg(key).query_async(con).await?;
+            let entries: Vec<redis::Value> = redis::cmd("XRANGE").arg(key).arg("-").arg("+").arg("COUNT").arg(STREAM_VALUE_ENTRIES).query_async(con).await?;
+            let entries: Vec<RawStreamEntry> = nested_values(&entries)?;
+            Ok(RedisValue::Stream(StreamValue { length, entries: entries.into_iter().map(stream_entry).collect() }))
+        }
+        "none" => Err(KVAdminerError::KeyNotFound(key.to_string())),
+        other => Ok(RedisValue::Unknown(other.to_string())),
+    }
+}
+
+// Elements of a list, sorted set or hash, and bytes of a string, shown in a row of the key listing
+const PREVIEW_ITEMS: usize = 100;
+const PREVIEW_BYTES: usize = 1024;
+
+/// Reads the start of a value for a row of the key listing, so one huge key cannot make a page
+/// slow. Also returns the full size of strings (bytes) and of lists, sorted sets and hashes
+/// (elements); sets and streams carry theirs in the value.
+pub async fn get_value_preview<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<(RedisValue, Option<u64>), KVAdminerError> {
+    let data_type: String = redis::cmd("TYPE").arg(key).query_async(con).await?;
+    match data_type.as_str() {
+        "string" => {
+            let size: u64 = con.strlen(key).await?;
+            let mut value: Vec<u8> = con.getrange(key, 0, PREVIEW_BYTES as isize - 1).await?;
+            // Do not cut a UTF-8 text in the middle of a character
+            if let Err(err) = std::str::from_utf8(&value) {
+                if err.error_len().is_none() {
+                    value.truncate(err.valid_up_to());
+                }
+            }
+            Ok((RedisValue::String(RedisBytes(value)), Some(size)))
+        }
+        "list" => {
+            let size: u64 = con.llen(key).await?;
+            let values: Vec<Vec<u8>> = con.lrange(key, 0, PREVIEW_ITEMS as isize - 1).await?;
+            Ok((RedisValue::List(values.into_iter().map(RedisBytes).collect()), Some(size)))
+        }
+        "zset" => {
+            let size: u64 = con.zcard(key).await?;
+            let members: Vec<(Vec<u8>, f64)> = con.zrange_withscores(key, 0, PREVIEW_ITEMS as isize - 1).await?;
+            let members = members.into_iter().map(|(member, score)| ScoredMember { member: RedisBytes(member), score }).collect();
+            Ok((RedisValue::ZSet(members), Some(size)))
+        }
+        "hash" => {
+            // HSCAN rather than HGETALL; fields of a page may repeat, which the BTreeMap drops
+            let size: u64 = con.hlen(key).await?;
+            let mut fields = BTreeMap::new();
+            let mut cursor = 0;
+            for _ in 0..MAX_SCAN_CALLS_PER_PAGE {
+                let (new_cursor, batch): (u64, BTreeMap<Vec<u8>, Vec<u8>>) =
+                    redis::cmd("HSCAN").arg(key).cursor_arg(cursor).arg("COUNT").arg(PREVIEW_ITEMS).query_async(con).await?;
+                fields.extend(batch);
+                cursor = new_cursor;
+                if cursor == 0 || fields.len() >= PREVIEW_ITEMS {
+                    break;
+                }
+            }
+            let fields = fields
+                .into_iter()
+                .take(PREVIEW_ITEMS)
+                .map(|(field, value)| HashField { field: RedisBytes(field), value: RedisBytes(value) })
+                .collect();
+            Ok((RedisValue::Hash(fields), Some(size)))
+        }
+        _ => Ok((get_redis_value(con, key).await?, None)),
+    }
+}
+
+/// Expiration of a key, given as `{"ex": 60}`, `{"px": 1500}`, `{"exat": <unix seconds>}`,
+/// `{"pxat": <unix milliseconds>}` or `"keep_ttl"`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expiry {
+    Ex(u64),
+    Px(u64),
+    Exat(u64),
+    Pxat(u64),
+    KeepTtl, // Only valid for writes: keep the expiration the key had before
+}
+
+impl Expiry {
+    fn validate(&self) -> Result<(), KVAdminerError> {
+        match self {
+            Expiry::Ex(0) | Expiry::Px(0) | Expiry::Exat(0) | Expiry::Pxat(0) => {
+                Err(KVAdminerError::InvalidInput("expiry must be greater than zero".to_string()))
+            }
+            _ => Ok(()),
         }
     }
+
+    /// The EXPIRE family command applying this expiry, `None` for `KeepTtl`.
+    fn expire_cmd(&self, key: &RedisKey) -> Option<redis::Cmd> {
+        let (name, time) = match *self {
+            Expiry::Ex(seconds) => ("EXPIRE", seconds),
+            Expiry::Px(millis) => ("PEXPIRE", millis),
+            Expiry::Exat(timestamp) => ("EXPIREAT", timestamp),
+            Expiry::Pxat(timestamp) => ("PEXPIREAT", timestamp),
+            Expiry::KeepTtl => return None,
+        };
+        let mut cmd = redis::cmd(name);
+        cmd.arg(key).arg(time);
+        Some(cmd)
+    }
+}
+
+/// Remaining time to live of `key` in milliseconds, `None` when it does not expire (or is gone).
+pub async fn get_ttl<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<Option<i64>, KVAdminerError> {
+    let ttl: i64 = con.pttl(key).await?;
+    Ok((ttl >= 0).then_some(ttl))
 }
 
-pub fn set_redis_value(con: &mut redis::Connection, key: &str, value: &str, value_type: &RedisValueType) -> Result<(), KVAdminerError> {
-    match value_type {
-        RedisValueType::String => {
-            let result: redis::RedisResult<()> = con.set(key, value);
-            result.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+/// Sets the expiration of an existing key. Returns `false` if the key does not exist.
+pub async fn expire_key<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, expiry: &Expiry) -> Result<bool, KVAdminerError> {
+    expiry.validate()?;
+    let Some(cmd) = expiry.expire_cmd(key) else {
+        return Err(KVAdminerError::InvalidInput("keep_ttl can only be used when writing a value".to_string()));
+    };
+    Ok(cmd.query_async(con).await?)
+}
+
+/// Removes the expiration of `key`. Returns `false` if the key does not exist.
+pub async fn persist_key<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<bool, KVAdminerError> {
+    let persisted: bool = con.persist(key).await?;
+    // PERSIST also answers 0 for keys without an expiration
+    Ok(persisted || con.exists(key).await?)
+}
+
+/// Replaces the value of `key`, without expiration unless `expiry` says otherwise. Collections are
+/// deleted and rewritten inside MULTI/EXEC, so a failed write leaves the previous value in place.
+pub async fn set_redis_value<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, value: &RedisValue, expiry: Option<&Expiry>) -> Result<(), KVAdminerError> {
+    if let Some(expiry) = expiry {
+        expiry.validate()?;
+    }
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    // Streams are only ever created here, so there is nothing to delete first
+    if !matches!(value, RedisValue::Stream(_)) {
+        pipe.del(key).ignore();
+    }
+    match value {
+        RedisValue::String(value) => {
+            let options = match expiry {
+                Some(Expiry::Ex(seconds)) => SetOptions::default().with_expiration(SetExpiry::EX(*seconds as usize)),
+                Some(Expiry::Px(millis)) => SetOptions::default().with_expiration(SetExpiry::PX(*millis as usize)),
+                Some(Expiry::Exat(timestamp)) => SetOptions::default().with_expiration(SetExpiry::EXAT(*timestamp as usize)),
+                Some(Expiry::Pxat(timestamp)) => SetOptions::default().with_expiration(SetExpiry::PXAT(*timestamp as usize)),
+                Some(Expiry::KeepTtl) => SetOptions::default().with_expiration(SetExpiry::KEEPTTL),
+                None => SetOptions::default(),
+            };
+            let result: redis::RedisResult<()> = con.set_options(key, &value.0, options).await;
+            return result.map_err(KVAdminerError::from);
+        },
+        RedisValue::Unknown(_) => return Err(KVAdminerError::TypeError),
+        // Redis has no empty collections, writing one would only delete the key
+        RedisValue::List(values) if values.is_empty() => {
+            return Err(KVAdminerError::InvalidInput("a collection needs at least one element".to_string()));
         },
-        RedisValueType::List => {
-            let values: Vec<&str> = value.split(',').collect();
-            con.del(key as &str).map_err(|err| KVAdminerError::RedisError(err.to_string()))?;
-            let result: redis::RedisResult<()> = con.rpush(key, values);
-            result.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+        RedisValue::Set(set) if set.members.is_empty() => {
+            return Err(KVAdminerError::InvalidInput("a collection needs at least one element".to_string()));
         },
-        RedisValueType::Set => {
-            let values: Vec<&str> = value.split(',').collect();
-            con.del(key as &str).map_err(|err| KVAdminerError::RedisError(err.to_string()))?;
-            let result: redis::RedisResult<()> = con.sadd(key, values);
-            result.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+        // `/get` only returns the first members, sending such a value back would drop the others
+        RedisValue::Set(set) if set.size != 0 && set.size != set.members.len() as u64 => {
+            return Err(KVAdminerError::InvalidInput(format!(
+                "the set has {} members but the value holds {}; edit large sets member by member",
+                set.size,
+                set.members.len()
+            )));
         },
-        RedisValueType::ZSet => {
-            let values: Vec<&str> = value.split(',').collect();
-            con.del(key as &str).map_err(|err| KVAdminerError::RedisError(err.to_string()))?;
-            let score_value_pairs: Vec<(i64, &str)> = values.iter().enumerate().map(|(i, &v)| (i as i64, v)).collect();
-            let result: redis::RedisResult<()> = con.zadd_multiple(key, &score_value_pairs);
-            result.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+        RedisValue::ZSet(members) if members.is_empty() => {
+            return Err(KVAdminerError::InvalidInput("a sorted set needs at least one member".to_string()));
         },
-        RedisValueType::Hash => {
-            con.del(key as &str).map_err(|err| KVAdminerError::RedisError(err.to_string()))?;
-            let kv_pairs: Vec<(&str, &str)> = value.split(',').map(|pair| {
-                let mut split = pair.split(':');
-                let k = split.next().unwrap().trim();
-                let v = split.next().unwrap_or("").trim();
-                (k, v)
-            }).collect();
-            let result: redis::RedisResult<()> = con.hset_multiple(key, &kv_pairs);
-            result.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+        RedisValue::Hash(fields) if fields.is_empty() => {
+            return Err(KVAdminerError::InvalidInput("a hash needs at least one field".to_string()));
         },
-        _ => Err(KVAdminerError::TypeError)
+        RedisValue::Stream(stream) if stream.entries.is_empty() => {
+            return Err(KVAdminerError::InvalidInput("a stream needs at least one entry".to_string()));
+        },
+        RedisValue::List(values) => pipe.rpush(key, values.iter().map(|v| v.0.as_slice()).collect::<Vec<_>>()).ignore(),
+        RedisValue::Set(set) => pipe.sadd(key, set.members.iter().map(|v| v.0.as_slice()).collect::<Vec<_>>()).ignore(),
+        RedisValue::ZSet(members) => {
+            let pairs: Vec<(f64, &[u8])> = members.iter().map(|m| (m.score, m.member.0.as_slice())).collect();
+            pipe.zadd_multiple(key, &pairs).ignore()
+        },
+        RedisValue::Hash(fields) => {
+            let pairs: Vec<(&[u8], &[u8])> = fields.iter().map(|f| (f.field.0.as_slice(), f.value.0.as_slice())).collect();
+            pipe.hset_multiple(key, &pairs).ignore()
+        },
+        RedisValue::Stream(stream) => {
+            // `/get` only returns the first entries, sending such a value back would truncate the stream
+            if stream.length != 0 && stream.length != stream.entries.len() as u64 {
+                return Err(KVAdminerError::InvalidInput(format!(
+                    "the stream has {} entries but the value holds {}; edit existing streams entry by entry",
+                    stream.length,
+                    stream.entries.len()
+                )));
+            }
+            // Rewriting a stream would also drop its consumer groups
+            if con.exists::<_, bool>(key).await? {
+                return Err(KVAdminerError::Conflict(format!(
+                    "{} already exists; streams can only be created as a whole, edit existing ones entry by entry",
+                    key
+                )));
+            }
+            validate_stream_ids(&stream.entries)?;
+            for entry in &stream.entries {
+                pipe.add_command(xadd_cmd(key, &entry.id, &entry.fields)).ignore();
+            }
+            &mut pipe
+        },
+    };
+    match expiry {
+        // DEL drops the expiration, so it is read beforehand and put back after the rewrite
+        Some(Expiry::KeepTtl) => {
+            if let Some(ttl) = get_ttl(con, key).await? {
+                pipe.pexpire(key, ttl).ignore();
+            }
+        }
+        Some(expiry) => {
+            if let Some(cmd) = expiry.expire_cmd(key) {
+                pipe.add_command(cmd).ignore();
+            }
+        }
+        None => {}
+    }
+    let result: redis::RedisResult<()> = pipe.query_async(con).await;
+    result.map_err(KVAdminerError::from)
+}
+
+pub async fn get_redis_hash<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<HashMap<String, String>, KVAdminerError> {
+    let value: HashMap<String, String> = con.hgetall(key).await?;
+    // HGETALL answers an empty hash for missing keys as well
+    if value.is_empty() && !con.exists::<_, bool>(key).await? {
+        return Err(KVAdminerError::KeyNotFound(key.to_string()));
     }
+    Ok(value)
+}
+
+pub async fn set_redis_hash<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, field: &str, value: &str) -> Result<(), KVAdminerError> {
+    let result: redis::RedisResult<()> = con.hset(key, field, value).await;
+    result.map_err(KVAdminerError::from)
 }
 
-pub fn get_redis_hash(con: &mut redis::Connection, key: &str) -> Result<HashMap<String, String>, KVAdminerError> {
-    let value: redis::RedisResult<HashMap<String, String>> = con.hgetall(key);
-    value.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+/// Which end of a list LPUSH / RPUSH and LPOP / RPOP work on.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListEnd {
+    Head, // LPUSH / LPOP
+    Tail, // RPUSH / RPOP
 }
 
-pub fn set_redis_hash(con: &mut redis::Connection, key: &str, field: &str, value: &str) -> Result<(), KVAdminerError> {
-    let result: redis::RedisResult<()> = con.hset(key, field, value);
-    result.map_err(|err| KVAdminerError::RedisError(err.to_string()))
+/// Where LINSERT puts the new element relative to the pivot.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertPosition {
+    Before,
+    After,
+}
+
+/// Reads up to `count` elements from index `start` on, together with the length of the list.
+///
+/// A negative `start` counts from the end of the list, like LRANGE does; the returned start is
+/// always the absolute index of the first element.
+pub async fn get_list_range<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, start: i64, count: usize) -> Result<(u64, u64, Vec<RedisBytes>), KVAdminerError> {
+    let length: u64 = con.llen(key).await?;
+    if length == 0 {
+        // Redis removes lists once they are empty, so an empty list is a missing key
+        require_key(con, key).await?;
+    }
+    let start = if start < 0 { length.saturating_sub(start.unsigned_abs()) } else { start as u64 };
+    if count == 0 || start >= length {
+        return Ok((length, start, vec![]));
+    }
+    let stop = start.saturating_add(count as u64 - 1);
+    let values: Vec<Vec<u8>> = con.lrange(key, start as isize, stop.min(isize::MAX as u64) as isize).await?;
+    Ok((length, start, values.into_iter().map(RedisBytes).collect()))
+}
+
+/// Replaces the element at `index` (negative counts from the end).
+pub async fn set_list_element<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, index: i64, value: &RedisBytes) -> Result<(), KVAdminerError> {
+    let result: redis::RedisResult<()> = redis::cmd("LSET").arg(key).arg(index).arg(value.0.as_slice()).query_async(con).await;
+    result.map_err(|err| match err.detail() {
+        Some(detail) if detail.contains("no such key") => KVAdminerError::KeyNotFound(key.to_string()),
+        Some(detail) if detail.contains("out of range") => KVAdminerError::InvalidInput(format!("index {} is out of range", index)),
+        _ => KVAdminerError::from(err),
+    })
+}
+
+/// Inserts `value` next to the first element equal to `pivot` and returns the new length.
+pub async fn insert_list_element<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    position: InsertPosition,
+    pivot: &RedisBytes,
+    value: &RedisBytes,
+) -> Result<u64, KVAdminerError> {
+    let length: i64 = redis::cmd("LINSERT")
+        .arg(key)
+        .arg(match position {
+            InsertPosition::Before => "BEFORE",
+            InsertPosition::After => "AFTER",
+        })
+        .arg(pivot.0.as_slice())
+        .arg(value.0.as_slice())
+        .query_async(con)
+        .await?;
+    match length {
+        0 => Err(KVAdminerError::KeyNotFound(key.to_string())),
+        -1 => Err(KVAdminerError::InvalidInput("the pivot element is not in the list".to_string())),
+        length => Ok(length as u64),
+    }
+}
+
+/// Pushes `values` in order onto one end of the list, creating it if needed, and returns the new
+/// length. Pushed onto the head, the last value ends up first.
+pub async fn push_list_elements<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, end: ListEnd, values: &[RedisBytes]) -> Result<u64, KVAdminerError> {
+    if values.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no elements given".to_string()));
+    }
+    let values: Vec<&[u8]> = values.iter().map(|v| v.0.as_slice()).collect();
+    let length = match end {
+        ListEnd::Head => con.lpush(key, values).await?,
+        ListEnd::Tail => con.rpush(key, values).await?,
+    };
+    Ok(length)
+}
+
+/// Removes and returns up to `count` elements from one end of the list. A count above 1 needs
+/// Redis 6.2+.
+pub async fn pop_list_elements<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, end: ListEnd, count: usize) -> Result<Vec<RedisBytes>, KVAdminerError> {
+    let mut cmd = match end {
+        ListEnd::Head => redis::cmd("LPOP"),
+        ListEnd::Tail => redis::cmd("RPOP"),
+    };
+    cmd.arg(key);
+    // Without a count the reply is a single element, which older servers also understand
+    let values: Option<Vec<Vec<u8>>> = if count == 1 {
+        let value: Option<Vec<u8>> = cmd.query_async(con).await?;
+        value.map(|value| vec![value])
+    } else {
+        cmd.arg(count).query_async(con).await?
+    };
+    match values {
+        Some(values) => Ok(values.into_iter().map(RedisBytes).collect()),
+        None => Err(KVAdminerError::KeyNotFound(key.to_string())),
+    }
+}
+
+/// Removes elements equal to `value` and returns how many were removed: the first `count` from
+/// the head, the last `-count` from the tail for a negative count, or all of them for 0.
+pub async fn remove_list_elements<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, count: i64, value: &RedisBytes) -> Result<u64, KVAdminerError> {
+    require_key(con, key).await?;
+    Ok(con.lrem(key, count as isize, value.0.as_slice()).await?)
+}
+
+/// Keeps only the elements from `start` to `stop` (inclusive, negative counting from the end) and
+/// returns the new length. A range outside the list removes the key.
+pub async fn trim_redis_list<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, start: i64, stop: i64) -> Result<u64, KVAdminerError> {
+    require_key(con, key).await?;
+    let (_, length): ((), u64) = redis::pipe()
+        .atomic()
+        .ltrim(key, start as isize, stop as isize)
+        .llen(key)
+        .query_async(con)
+        .await?;
+    Ok(length)
+}
+
+// Members of a set included in `/get`, the rest is paged through `scan_set_page`
+const SET_VALUE_MEMBERS: usize = 100;
+
+/// Runs one SSCAN call over the members of a set.
+async fn sscan_batch<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, cursor: u64, pattern: &[u8], count: usize) -> Result<(u64, Vec<Vec<u8>>), KVAdminerError> {
+    Ok(redis::cmd("SSCAN").arg(key).cursor_arg(cursor).arg("MATCH").arg(pattern).arg("COUNT").arg(count).query_async(con).await?)
+}
+
+/// Runs SSCAN from `cursor` until at least `count` members matched, the set was walked or the call
+/// budget is spent. Returns the cursor to continue from, 0 once the scan is complete.
+///
+/// Like SCAN, a page can hold a few more members than `count`, and a member may show up twice
+/// when the set is resized between two pages.
+pub async fn scan_set_page<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, mut cursor: u64, pattern: &[u8], count: usize) -> Result<(u64, Vec<RedisBytes>), KVAdminerError> {
+    let mut members = vec![];
+    let mut hint = std::cmp::max(count, 1);
+    for _ in 0..MAX_SCAN_CALLS_PER_PAGE {
+        let (new_cursor, batch) = sscan_batch(con, key, cursor, pattern, hint).await?;
+
+        members.extend(batch.into_iter().map(RedisBytes));
+        cursor = new_cursor;
+        if cursor == 0 || members.len() >= count {
+            break;
+        }
+        hint = std::cmp::min(hint.saturating_mul(2), 1000);
+    }
+    if members.is_empty() && cursor == 0 {
+        // SSCAN answers a missing key like a set without matching members
+        require_key(con, key).await?;
+    }
+    Ok((cursor, members))
+}
+
+pub async fn get_set_size<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<u64, KVAdminerError> {
+    Ok(con.scard(key).await?)
+}
+
+/// Adds members to a set, creating it if needed, and returns how many were not in it yet.
+pub async fn add_set_members<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, members: &[RedisBytes]) -> Result<u64, KVAdminerError> {
+    if members.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no members given".to_string()));
+    }
+    Ok(con.sadd(key, members.iter().map(|m| m.0.as_slice()).collect::<Vec<_>>()).await?)
+}
+
+/// Removes members from a set and returns how many were in it.
+pub async fn remove_set_members<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, members: &[RedisBytes]) -> Result<u64, KVAdminerError> {
+    if members.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no members given".to_string()));
+    }
+    require_key(con, key).await?;
+    Ok(con.srem(key, members.iter().map(|m| m.0.as_slice()).collect::<Vec<_>>()).await?)
+}
+
+pub async fn set_has_member<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, member: &RedisBytes) -> Result<bool, KVAdminerError> {
+    let contains: bool = con.sismember(key, member.0.as_slice()).await?;
+    if !contains {
+        require_key(con, key).await?;
+    }
+    Ok(contains)
+}
+
+/// How `combine_sets` combines the members of several sets.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SetOperation {
+    Inter, // Members of every set
+    Union, // Members of any set
+    Diff, // Members of the first set that are in none of the others
+}
+
+impl SetOperation {
+    fn command(self, store: bool) -> &'static str {
+        match (self, store) {
+            (SetOperation::Inter, false) => "SINTER",
+            (SetOperation::Union, false) => "SUNION",
+            (SetOperation::Diff, false) => "SDIFF",
+            (SetOperation::Inter, true) => "SINTERSTORE",
+            (SetOperation::Union, true) => "SUNIONSTORE",
+            (SetOperation::Diff, true) => "SDIFFSTORE",
+        }
+    }
+}
+
+/// Combines the given sets and returns the size of the result together with its first `limit`
+/// members, sorted. Missing keys count as empty sets.
+///
+/// In cluster mode every key has to live in the same hash slot.
+pub async fn combine_sets<C: ConnectionLike + Send>(con: &mut C, operation: SetOperation, keys: &[RedisKey], limit: usize) -> Result<(u64, Vec<RedisBytes>), KVAdminerError> {
+    if keys.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no keys given".to_string()));
+    }
+    let mut members: Vec<Vec<u8>> = redis::cmd(operation.command(false)).arg(keys).query_async(con).await?;
+    members.sort();
+    let total = members.len() as u64;
+    members.truncate(limit);
+    Ok((total, members.into_iter().map(RedisBytes).collect()))
+}
+
+/// Stores the combination of the given sets in `destination` and returns its size. An existing
+/// destination is only replaced with `overwrite`; an empty result deletes it.
+pub async fn store_combined_sets<C: ConnectionLike + Send>(
+    con: &mut C,
+    operation: SetOperation,
+    keys: &[RedisKey],
+    destination: &RedisKey,
+    overwrite: bool,
+) -> Result<u64, KVAdminerError> {
+    if keys.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no keys given".to_string()));
+    }
+    if !overwrite && con.exists(destination).await? {
+        return Err(KVAdminerError::Conflict(format!("key {} already exists", destination)));
+    }
+    Ok(redis::cmd(operation.command(true)).arg(destination).arg(keys).query_async(con).await?)
+}
+
+/// What the bounds of a `ZRange` are compared against.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZRangeBy {
+    #[default]
+    Rank, // 0 based ranks, negative counting from the end
+    Score, // Scores like `1.5`, exclusive as `(1.5`, or `-inf` / `+inf`
+    Lex, // `[a` (inclusive), `(a` (exclusive), `-` or `+`, for members sharing one score
+}
+
+/// A range of a sorted set as ZRANGE and ZRANGESTORE take it.
+#[derive(Debug, Clone)]
+pub struct ZRange {
+    pub by: ZRangeBy,
+    pub min: String, // Start rank for `Rank`
+    pub max: String, // Stop rank (inclusive) for `Rank`
+    pub reverse: bool, // Highest scores first; ranks then count from the highest score
+}
+
+fn valid_score_bound(bound: &str) -> bool {
+    let value = bound.strip_prefix('(').unwrap_or(bound);
+    matches!(value, "-inf" | "+inf" | "inf") || value.parse::<f64>().is_ok_and(|score| !score.is_nan())
+}
+
+impl ZRange {
+    /// Builds a range, defaulting missing bounds to the whole set, and rejects bounds Redis would
+    /// not accept.
+    pub fn new(by: ZRangeBy, min: Option<String>, max: Option<String>, reverse: bool) -> Result<ZRange, KVAdminerError> {
+        let (default_min, default_max) = match by {
+            ZRangeBy::Rank => ("0", "-1"),
+            ZRangeBy::Score => ("-inf", "+inf"),
+            ZRangeBy::Lex => ("-", "+"),
+        };
+        let range = ZRange {
+            by,
+            min: min.unwrap_or_else(|| default_min.to_string()),
+            max: max.unwrap_or_else(|| default_max.to_string()),
+            reverse,
+        };
+        for bound in [&range.min, &range.max] {
+            let valid = match by {
+                ZRangeBy::Rank => bound.parse::<i64>().is_ok(),
+                ZRangeBy::Score => valid_score_bound(bound),
+                ZRangeBy::Lex => bound == "-" || bound == "+" || bound.starts_with('[') || bound.starts_with('('),
+            };
+            if !valid {
+                let kind = match by {
+                    ZRangeBy::Rank => "rank",
+                    ZRangeBy::Score => "score",
+                    ZRangeBy::Lex => "lex",
+                };
+                return Err(KVAdminerError::InvalidInput(format!("invalid {} bound: {}", kind, bound)));
+            }
+        }
+        Ok(range)
+    }
+
+    /// Appends the bounds and the BYSCORE / BYLEX and REV options, in the order ZRANGE expects.
+    fn append_to(&self, cmd: &mut redis::Cmd, start: &str, stop: &str) {
+        cmd.arg(start).arg(stop);
+        match self.by {
+            ZRangeBy::Rank => {}
+            ZRangeBy::Score => {
+                cmd.arg("BYSCORE");
+            }
+            ZRangeBy::Lex => {
+                cmd.arg("BYLEX");
+            }
+        }
+        if self.reverse {
+            cmd.arg("REV");
+        }
+    }
+
+    /// With REV, score and lex ranges are given from the high end to the low end.
+    fn bounds(&self) -> (&str, &str) {
+        match self.by {
+            ZRangeBy::Score | ZRangeBy::Lex if self.reverse => (&self.max, &self.min),
+            _ => (&self.min, &self.max),
+        }
+    }
+}
+
+// Turns a possibly negative rank into an index, clamping ranks before the first member to 0
+fn resolve_rank(rank: i64, size: u64) -> u64 {
+    if rank < 0 {
+        size.saturating_sub(rank.unsigned_abs())
+    } else {
+        rank as u64
+    }
+}
+
+/// Reads up to `count` members of `range`, skipping the first `offset`, together with their scores
+/// and the size of the whole set. Also returns the offset the next page starts at, `None` when the
+/// range is exhausted.
+///
+/// Score and lex ranges need Redis 6.2+.
+pub async fn get_zset_range<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    range: &ZRange,
+    offset: u64,
+    count: usize,
+) -> Result<(u64, Vec<ScoredMember>, Option<u64>), KVAdminerError> {
+    let size: u64 = con.zcard(key).await?;
+    if size == 0 {
+        // Like lists, sorted sets are removed once they are empty
+        require_key(con, key).await?;
+    }
+    let mut cmd = redis::cmd("ZRANGE");
+    cmd.arg(key);
+    match range.by {
+        ZRangeBy::Rank => {
+            // Ranks have no LIMIT, so the page is cut out of the range by hand
+            let start = resolve_rank(range.min.parse().unwrap_or(0), size).saturating_add(offset);
+            let stop = match range.max.parse::<i64>().unwrap_or(-1) {
+                stop if stop < 0 && stop.unsigned_abs() > size => return Ok((size, vec![], None)),
+                stop => resolve_rank(stop, size).min(size.saturating_sub(1)),
+            };
+            if start > stop || start >= size {
+                return Ok((size, vec![], None));
+            }
+            let page_stop = stop.min(start.saturating_add(count as u64));
+            range.append_to(&mut cmd, &start.to_string(), &page_stop.to_string());
+        }
+        ZRangeBy::Score | ZRangeBy::Lex => {
+            let (start, stop) = range.bounds();
+            range.append_to(&mut cmd, start, stop);
+            cmd.arg("LIMIT").arg(offset).arg(count + 1);
+        }
+    }
+    let mut members: Vec<ScoredMember> = match range.by {
+        // BYLEX cannot be combined with WITHSCORES, the scores are looked up afterwards
+        ZRangeBy::Lex => {
+            let names: Vec<Vec<u8>> = cmd.query_async(con).await?;
+            let scores: Vec<Option<f64>> = if names.is_empty() {
+                vec![]
+            } else {
+                redis::cmd("ZMSCORE").arg(key).arg(&names).query_async(con).await?
+            };
+            names
+                .into_iter()
+                .zip(scores)
+                .filter_map(|(member, score)| score.map(|score| ScoredMember { member: RedisBytes(member), score }))
+                .collect()
+        }
+        _ => {
+            let pairs: Vec<(Vec<u8>, f64)> = cmd.arg("WITHSCORES").query_async(con).await?;
+            pairs.into_iter().map(|(member, score)| ScoredMember { member: RedisBytes(member), score }).collect()
+        }
+    };
+    // One member more than asked for tells whether there is a next page
+    let next = if members.len() > count {
+        members.truncate(count);
+        Some(offset + count as u64)
+    } else {
+        None
+    };
+    Ok((size, members, next))
+}
+
+/// Conditions of ZADD: `nx` only adds new members and `xx` only updates existing ones, while `gt`
+/// / `lt` only update a score when the new one is greater / less than the current one.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct ZAddFlags {
+    #[serde(default)]
+    pub nx: bool,
+    #[serde(default)]
+    pub xx: bool,
+    #[serde(default)]
+    pub gt: bool, // Redis 6.2+
+    #[serde(default)]
+    pub lt: bool, // Redis 6.2+
+}
+
+/// Adds members with their scores, or updates the scores of existing ones, and returns how many
+/// members were added or got a new score (ZADD CH).
+pub async fn add_zset_members<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, members: &[ScoredMember], flags: ZAddFlags) -> Result<u64, KVAdminerError> {
+    if members.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no members given".to_string()));
+    }
+    if (flags.nx && flags.xx) || (flags.gt && flags.lt) || (flags.nx && (flags.gt || flags.lt)) {
+        return Err(KVAdminerError::InvalidInput("nx cannot be combined with xx, gt or lt, nor gt with lt".to_string()));
+    }
+    let mut cmd = redis::cmd("ZADD");
+    cmd.arg(key);
+    for (set, flag) in [(flags.nx, "NX"), (flags.xx, "XX"), (flags.gt, "GT"), (flags.lt, "LT")] {
+        if set {
+            cmd.arg(flag);
+        }
+    }
+    cmd.arg("CH");
+    for ScoredMember { member, score } in members {
+        cmd.arg(*score).arg(member.0.as_slice());
+    }
+    Ok(cmd.query_async(con).await?)
+}
+
+/// Adds `increment` to the score of a member, adding it with that score if needed, and returns
+/// the new score.
+pub async fn increment_zset_member<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, member: &RedisBytes, increment: f64) -> Result<f64, KVAdminerError> {
+    Ok(con.zincr(key, member.0.as_slice(), increment).await?)
+}
+
+/// Removes members from a sorted set and returns how many were in it.
+pub async fn remove_zset_members<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, members: &[RedisBytes]) -> Result<u64, KVAdminerError> {
+    if members.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no members given".to_string()));
+    }
+    require_key(con, key).await?;
+    Ok(con.zrem(key, members.iter().map(|m| m.0.as_slice()).collect::<Vec<_>>()).await?)
+}
+
+/// Copies a range of a sorted set, scores included, into `destination` and returns its size
+/// (ZRANGESTORE, Redis 6.2+). `offset` and `count` only apply to score and lex ranges; without a
+/// count every member after the offset is copied.
+/// An existing destination is only replaced with `overwrite`; an empty range deletes it.
+///
+/// In cluster mode both keys have to live in the same hash slot.
+pub async fn store_zset_range<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    range: &ZRange,
+    offset: Option<u64>,
+    count: Option<u64>,
+    destination: &RedisKey,
+    overwrite: bool,
+) -> Result<u64, KVAdminerError> {
+    let limited = offset.is_some() || count.is_some();
+    if limited && matches!(range.by, ZRangeBy::Rank) {
+        return Err(KVAdminerError::InvalidInput("offset and count only apply to score and lex ranges".to_string()));
+    }
+    require_key(con, key).await?;
+    if !overwrite && con.exists(destination).await? {
+        return Err(KVAdminerError::Conflict(format!("key {} already exists", destination)));
+    }
+    let mut cmd = redis::cmd("ZRANGESTORE");
+    cmd.arg(destination).arg(key);
+    let (start, stop) = range.bounds();
+    range.append_to(&mut cmd, start, stop);
+    if limited {
+        // A negative count takes everything after the offset
+        cmd.arg("LIMIT").arg(offset.unwrap_or(0)).arg(count.map_or(-1, |count| count as i64));
+    }
+    Ok(cmd.query_async(con).await?)
+}
+
+// Entries of a stream included in `/get`, the rest is paged through `get_stream_range`
+const STREAM_VALUE_ENTRIES: usize = 100;
+
+// An entry as XRANGE returns it: the id and the flattened field / value pairs
+type RawStreamEntry = (String, Vec<Vec<u8>>);
+
+// `Vec<(A, B)>` reads a RESP2 array as flattened pairs, so arrays of arrays are converted one by one
+fn nested_values<T: redis::FromRedisValue>(values: &[redis::Value]) -> redis::RedisResult<Vec<T>> {
+    values.iter().map(redis::from_redis_value).collect()
+}
+
+fn stream_entry((id, values): RawStreamEntry) -> StreamEntry {
+    let fields = values
+        .chunks_exact(2)
+        .map(|pair| HashField { field: RedisBytes(pair[0].clone()), value: RedisBytes(pair[1].clone()) })
+        .collect();
+    StreamEntry { id, fields }
+}
+
+fn xadd_cmd(key: &RedisKey, id: &str, fields: &[HashField]) -> redis::Cmd {
+    let mut cmd = redis::cmd("XADD");
+    cmd.arg(key).arg(id);
+    for field in fields {
+        cmd.arg(&field.field.0).arg(&field.value.0);
+    }
+    cmd
+}
+
+/// Parses an explicit stream entry id, `<milliseconds>-<sequence>`.
+fn parse_stream_id(id: &str) -> Option<(u64, u64)> {
+    let (millis, sequence) = id.split_once('-')?;
+    Some((millis.parse().ok()?, sequence.parse().ok()?))
+}
+
+fn invalid_stream_id(id: &str) -> KVAdminerError {
+    KVAdminerError::InvalidInput(format!("invalid stream entry id: {}", id))
+}
+
+/// Rejects ids of existing entries that are not `<milliseconds>-<sequence>`, before Redis answers
+/// them with a generic error.
+fn require_stream_id(id: &str) -> Result<(), KVAdminerError> {
+    parse_stream_id(id).map(|_| ()).ok_or_else(|| invalid_stream_id(id))
+}
+
+/// Accepts the ids XADD takes for a new entry: `*`, `<milliseconds>-*` or an explicit id above `0-0`.
+fn validate_new_stream_id(id: &str) -> Result<(), KVAdminerError> {
+    let valid = match id.strip_suffix("-*") {
+        _ if id == "*" => true,
+        Some(millis) => millis.parse::<u64>().is_ok(),
+        None => parse_stream_id(id).is_some_and(|id| id != (0, 0)),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid_stream_id(id))
+    }
+}
+
+/// Checks the entries of a stream written in one go, so XADD cannot fail half way through the
+/// rewrite: every entry has fields and explicit ids increase and do not follow a generated (`*`) one.
+fn validate_stream_ids(entries: &[StreamEntry]) -> Result<(), KVAdminerError> {
+    let mut last = (0, 0);
+    let mut generated = false;
+    for entry in entries {
+        if entry.fields.is_empty() {
+            return Err(KVAdminerError::InvalidInput("a stream entry needs at least one field".to_string()));
+        }
+        if entry.id == "*" {
+            generated = true;
+            continue;
+        }
+        let id = parse_stream_id(&entry.id).ok_or_else(|| invalid_stream_id(&entry.id))?;
+        if generated || id <= last {
+            return Err(KVAdminerError::InvalidInput(format!("stream entry ids must increase and cannot follow `*`: {}", entry.id)));
+        }
+        last = id;
+    }
+    Ok(())
+}
+
+async fn require_key<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<(), KVAdminerError> {
+    if con.exists(key).await? {
+        Ok(())
+    } else {
+        Err(KVAdminerError::KeyNotFound(key.to_string()))
+    }
+}
+
+/// Reads up to `count` entries between the ids `start` and `end` (inclusive, `-` and `+` for the
+/// ends of the stream), newest first when `reverse` is set.
+///
+/// Also returns the id the next page starts at, `None` when the range is exhausted.
+pub async fn get_stream_range<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    start: &str,
+    end: &str,
+    count: usize,
+    reverse: bool,
+) -> Result<(Vec<StreamEntry>, Option<String>), KVAdminerError> {
+    let mut cmd = if reverse { redis::cmd("XREVRANGE") } else { redis::cmd("XRANGE") };
+    cmd.arg(key);
+    if reverse {
+        cmd.arg(end).arg(start);
+    } else {
+        cmd.arg(start).arg(end);
+    }
+    // One entry more than asked for tells where the next page starts
+    let entries: Vec<redis::Value> = cmd.arg("COUNT").arg(count + 1).query_async(con).await?;
+    let entries: Vec<RawStreamEntry> = nested_values(&entries)?;
+    let mut entries: Vec<StreamEntry> = entries.into_iter().map(stream_entry).collect();
+    if entries.is_empty() {
+        require_key(con, key).await?;
+    }
+    let next = if entries.len() > count { entries.pop().map(|entry| entry.id) } else { None };
+    Ok((entries, next))
+}
+
+/// Appends an entry with the given id (`*` to let Redis generate one) and returns its id.
+pub async fn add_redis_stream_entry<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, id: &str, fields: &[HashField]) -> Result<String, KVAdminerError> {
+    if fields.is_empty() {
+        return Err(KVAdminerError::InvalidInput("a stream entry needs at least one field".to_string()));
+    }
+    validate_new_stream_id(id)?;
+    Ok(xadd_cmd(key, id, fields).query_async(con).await?)
+}
+
+/// Deletes entries by id and returns how many existed.
+pub async fn delete_redis_stream_entries<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, ids: &[String]) -> Result<u64, KVAdminerError> {
+    if ids.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no entry ids given".to_string()));
+    }
+    ids.iter().try_for_each(|id| require_stream_id(id))?;
+    Ok(redis::cmd("XDEL").arg(key).arg(ids).query_async(con).await?)
+}
+
+/// How XTRIM shortens a stream, given as `{"maxlen": 1000}` or `{"minid": "1700000000000-0"}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamTrim {
+    Maxlen(u64), // Keep the newest entries only
+    Minid(String), // Drop entries older than the id (Redis 6.2+)
+}
+
+/// Trims a stream and returns the number of entries removed. With `approximate` Redis may keep a
+/// few more entries, which is much cheaper on large streams.
+pub async fn trim_redis_stream<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, trim: &StreamTrim, approximate: bool) -> Result<u64, KVAdminerError> {
+    if let StreamTrim::Minid(id) = trim {
+        require_stream_id(id)?;
+    }
+    let mut cmd = redis::cmd("XTRIM");
+    cmd.arg(key);
+    match trim {
+        StreamTrim::Maxlen(length) => cmd.arg("MAXLEN").arg(if approximate { "~" } else { "=" }).arg(*length),
+        StreamTrim::Minid(id) => cmd.arg("MINID").arg(if approximate { "~" } else { "=" }).arg(id),
+    };
+    Ok(cmd.query_async(con).await?)
+}
+
+/// What XINFO STREAM and XINFO GROUPS report about a stream.
+#[derive(Debug, Serialize)]
+pub struct StreamInfo {
+    pub length: u64,
+    pub last_generated_id: Option<String>,
+    pub first_entry_id: Option<String>,
+    pub last_entry_id: Option<String>,
+    pub groups: Vec<StreamGroup>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamGroup {
+    pub name: String,
+    pub consumers: u64,
+    pub pending: u64, // Entries delivered but not acknowledged yet
+    pub last_delivered_id: Option<String>,
+    pub entries_read: Option<u64>, // Redis 7.0+
+    pub lag: Option<u64>, // Entries not delivered to the group yet, Redis 7.0+
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreamConsumer {
+    pub name: String,
+    pub pending: u64,
+    pub idle_ms: u64, // Since the consumer last read
+    pub inactive_ms: Option<i64>, // Since its last successful read, Redis 7.2+; -1 if it never read
+}
+
+/// Pending entries of a group, as reported by the summary form of XPENDING.
+#[derive(Debug, Serialize)]
+pub struct PendingSummary {
+    pub count: u64,
+    pub min_id: Option<String>,
+    pub max_id: Option<String>,
+    pub consumers: Vec<ConsumerPending>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsumerPending {
+    pub name: String,
+    pub pending: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingEntry {
+    pub id: String,
+    pub consumer: String,
+    pub idle_ms: u64, // Since the entry was last delivered
+    pub deliveries: u64,
+}
+
+fn map_string(map: &HashMap<String, &redis::Value>, field: &str) -> Option<String> {
+    map.get(field).and_then(|value| value_to_string(value))
+}
+
+fn map_int(map: &HashMap<String, &redis::Value>, field: &str) -> Option<i64> {
+    map_string(map, field).and_then(|value| value.parse().ok())
+}
+
+// First and last entries are reported as `[id, [field, value, ...]]`
+fn map_entry_id(map: &HashMap<String, &redis::Value>, field: &str) -> Option<String> {
+    match map.get(field) {
+        Some(redis::Value::Bulk(entry)) => entry.first().and_then(value_to_string),
+        _ => None,
+    }
+}
+
+pub async fn get_stream_info<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey) -> Result<StreamInfo, KVAdminerError> {
+    require_key(con, key).await?;
+    let stream: redis::Value = redis::cmd("XINFO").arg("STREAM").arg(key).query_async(con).await?;
+    let groups: Vec<redis::Value> = redis::cmd("XINFO").arg("GROUPS").arg(key).query_async(con).await?;
+    let stream = value_to_map(&stream);
+    let groups = groups
+        .iter()
+        .map(|group| {
+            let group = value_to_map(group);
+            StreamGroup {
+                name: map_string(&group, "name").unwrap_or_default(),
+                consumers: map_int(&group, "consumers").unwrap_or(0) as u64,
+                pending: map_int(&group, "pending").unwrap_or(0) as u64,
+                last_delivered_id: map_string(&group, "last-delivered-id"),
+                entries_read: map_int(&group, "entries-read").map(|read| read as u64),
+                lag: map_int(&group, "lag").map(|lag| lag as u64),
+            }
+        })
+        .collect();
+    Ok(StreamInfo {
+        length: map_int(&stream, "length").unwrap_or(0) as u64,
+        last_generated_id: map_string(&stream, "last-generated-id"),
+        first_entry_id: map_entry_id(&stream, "first-entry"),
+        last_entry_id: map_entry_id(&stream, "last-entry"),
+        groups,
+    })
+}
+
+pub async fn get_stream_consumers<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, group: &str) -> Result<Vec<StreamConsumer>, KVAdminerError> {
+    let consumers: Vec<redis::Value> = redis::cmd("XINFO").arg("CONSUMERS").arg(key).arg(group).query_async(con).await?;
+    Ok(consumers
+        .iter()
+        .map(|consumer| {
+            let consumer = value_to_map(consumer);
+            StreamConsumer {
+                name: map_string(&consumer, "name").unwrap_or_default(),
+                pending: map_int(&consumer, "pending").unwrap_or(0) as u64,
+                idle_ms: map_int(&consumer, "idle").unwrap_or(0) as u64,
+                inactive_ms: map_int(&consumer, "inactive"),
+            }
+        })
+        .collect())
+}
+
+pub async fn get_pending_summary<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, group: &str) -> Result<PendingSummary, KVAdminerError> {
+    let (count, min_id, max_id, consumers): (u64, Option<String>, Option<String>, Option<Vec<redis::Value>>) =
+        redis::cmd("XPENDING").arg(key).arg(group).query_async(con).await?;
+    let consumers: Vec<(String, u64)> = nested_values(&consumers.unwrap_or_default())?;
+    Ok(PendingSummary {
+        count,
+        min_id,
+        max_id,
+        consumers: consumers.into_iter().map(|(name, pending)| ConsumerPending { name, pending }).collect(),
+    })
+}
+
+/// Which pending entries of a group to list.
+#[derive(Debug, Default)]
+pub struct PendingFilter<'a> {
+    pub consumer: Option<&'a str>, // Only the entries delivered to this consumer
+    pub min_idle_ms: Option<u64>, // Only entries not delivered for at least this long (Redis 6.2+)
+}
+
+/// Lists up to `count` pending entries of a group between `start` and `end`, with the id the next
+/// page starts at.
+pub async fn get_pending_entries<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    group: &str,
+    start: &str,
+    end: &str,
+    count: usize,
+    filter: &PendingFilter<'_>,
+) -> Result<(Vec<PendingEntry>, Option<String>), KVAdminerError> {
+    let mut cmd = redis::cmd("XPENDING");
+    cmd.arg(key).arg(group);
+    if let Some(min_idle_ms) = filter.min_idle_ms {
+        cmd.arg("IDLE").arg(min_idle_ms);
+    }
+    cmd.arg(start).arg(end).arg(count + 1);
+    if let Some(consumer) = filter.consumer {
+        cmd.arg(consumer);
+    }
+    let entries: Vec<redis::Value> = cmd.query_async(con).await?;
+    let entries: Vec<(String, String, u64, u64)> = nested_values(&entries)?;
+    let mut entries: Vec<PendingEntry> = entries
+        .into_iter()
+        .map(|(id, consumer, idle_ms, deliveries)| PendingEntry { id, consumer, idle_ms, deliveries })
+        .collect();
+    let next = if entries.len() > count { entries.pop().map(|entry| entry.id) } else { None };
+    Ok((entries, next))
+}
+
+/// Creates a consumer group that starts reading after `start_id` (`$` for new entries only, `0` for
+/// the whole stream). With `mkstream` a missing stream is created empty.
+pub async fn create_stream_group<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, group: &str, start_id: &str, mkstream: bool) -> Result<(), KVAdminerError> {
+    if !mkstream {
+        require_key(con, key).await?;
+    }
+    let mut cmd = redis::cmd("XGROUP");
+    cmd.arg("CREATE").arg(key).arg(group).arg(start_id);
+    if mkstream {
+        cmd.arg("MKSTREAM");
+    }
+    Ok(cmd.query_async(con).await?)
+}
+
+/// Destroys a consumer group together with its consumers and pending entries.
+pub async fn destroy_stream_group<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, group: &str) -> Result<(), KVAdminerError> {
+    require_key(con, key).await?;
+    let destroyed: bool = redis::cmd("XGROUP").arg("DESTROY").arg(key).arg(group).query_async(con).await?;
+    if destroyed {
+        Ok(())
+    } else {
+        Err(KVAdminerError::GroupNotFound(group.to_string()))
+    }
+}
+
+/// Moves the last delivered id of a group, e.g. to replay entries (`0`) or skip the backlog (`$`).
+/// `entries_read` sets the group's read counter used for lag tracking (Redis 7.0+).
+pub async fn set_stream_group_id<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, group: &str, id: &str, entries_read: Option<u64>) -> Result<(), KVAdminerError> {
+    require_key(con, key).await?;
+    let mut cmd = redis::cmd("XGROUP");
+    cmd.arg("SETID").arg(key).arg(group).arg(id);
+    if let Some(entries_read) = entries_read {
+        cmd.arg("ENTRIESREAD").arg(entries_read);
+    }
+    Ok(cmd.query_async(con).await?)
+}
+
+/// Removes a consumer from a group and returns how many pending entries it still had; those
+/// entries are no longer pending for anyone.
+pub async fn delete_stream_consumer<C: ConnectionLike + Send>(con: &mut C, key: &RedisKey, group: &str, consumer: &str) -> Result<u64, KVAdminerError> {
+    require_key(con, key).await?;
+    Ok(redis::cmd("XGROUP").arg("DELCONSUMER").arg(key).arg(group).arg(consumer).query_async(con).await?)
+}
+
+/// Hands pending entries idle for at least `min_idle_ms` over to `consumer` and returns the ids
+/// that were claimed. JUSTID is used, so the delivery counters are left alone.
+pub async fn claim_stream_entries<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    group: &str,
+    consumer: &str,
+    min_idle_ms: u64,
+    ids: &[String],
+) -> Result<Vec<String>, KVAdminerError> {
+    if ids.is_empty() {
+        return Err(KVAdminerError::InvalidInput("no entry ids given".to_string()));
+    }
+    Ok(redis::cmd("XCLAIM")
+        .arg(key)
+        .arg(group)
+        .arg(consumer)
+        .arg(min_idle_ms)
+        .arg(ids)
+        .arg("JUSTID")
+        .query_async(con)
+        .await?)
+}
+
+/// Outcome of one XAUTOCLAIM call.
+#[derive(Debug, Serialize)]
+pub struct AutoClaimed {
+    pub claimed: Vec<String>,
+    pub deleted: Vec<String>, // Pending ids whose entries were deleted meanwhile, dropped from the group (Redis 7.0+)
+    pub next: Option<String>, // Id to continue scanning from, `None` once the whole pending list was seen
+}
+
+/// Claims up to `count` pending entries idle for at least `min_idle_ms`, scanning the pending list
+/// from `start`, for `consumer` (Redis 6.2+).
+pub async fn autoclaim_stream_entries<C: ConnectionLike + Send>(
+    con: &mut C,
+    key: &RedisKey,
+    group: &str,
+    consumer: &str,
+    min_idle_ms: u64,
+    start: &str,
+    count: usize,
+) -> Result<AutoClaimed, KVAdminerError> {
+    let reply: Vec<redis::Value> = redis::cmd("XAUTOCLAIM")
+        .arg(key)
+        .arg(group)
+        .arg(consumer)
+        .arg(min_idle_ms)
+        .arg(start)
+        .arg("COUNT")
+        .arg(count)
+        .arg("JUSTID")
+        .query_async(con)
+        .await?;
+    let next: String = match reply.first() {
+        Some(next) => redis::from_redis_value(next)?,
+        None => "0-0".to_string(),
+    };
+    let claimed: Vec<String> = match reply.get(1) {
+        Some(claimed) => redis::from_redis_value(claimed)?,
+        None => vec![],
+    };
+    let deleted: Vec<String> = match reply.get(2) {
+        Some(deleted) => redis::from_redis_value(deleted)?,
+        None => vec![],
+    };
+    Ok(AutoClaimed { claimed, deleted, next: (next != "0-0").then_some(next) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{self, Listen, TestServer};
+
+    fn tcp_info(username: Option<&str>, password: Option<&str>) -> RedisInfo {
+        RedisInfo {
+            host: "localhost".to_string(),
+            port: "6379".to_string(),
+            username: username.map(str::to_string),
+            password: password.map(str::to_string),
+            db: Some(2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn connection_info_keeps_password_without_username() {
+        let connection_info = build_connection_info(&tcp_info(None, Some("secret")), "localhost", "6379").unwrap();
+        assert_eq!(connection_info.addr, redis::ConnectionAddr::Tcp("localhost".to_string(), 6379));
+        assert_eq!(connection_info.redis.username, None);
+        assert_eq!(connection_info.redis.password.as_deref(), Some("secret"));
+        assert_eq!(connection_info.redis.db, 2);
+    }
+
+    #[test]
+    fn connection_info_keeps_special_characters_in_credentials() {
+        let info = tcp_info(Some("ops:admin"), Some("p@ss:w/rd#1%"));
+        let connection_info = build_connection_info(&info, "localhost", "6379").unwrap();
+        assert_eq!(connection_info.redis.username.as_deref(), Some("ops:admin"));
+        assert_eq!(connection_info.redis.password.as_deref(), Some("p@ss:w/rd#1%"));
+    }
+
+    #[test]
+    fn connection_info_ignores_blank_credentials() {
+        let connection_info = build_connection_info(&tcp_info(Some(""), Some(" ")), "localhost", "6379").unwrap();
+        assert_eq!(connection_info.redis.username, None);
+        assert_eq!(connection_info.redis.password, None);
+    }
+
+    #[test]
+    fn connection_info_uses_tls_address() {
+        let info = RedisInfo { tls: Some(true), tls_insecure: Some(true), ..tcp_info(None, None) };
+        let connection_info = build_connection_info(&info, "[::1]", "6380").unwrap();
+        assert!(matches!(
+            connection_info.addr,
+            redis::ConnectionAddr::TcpTls { ref host, port: 6380, insecure: true, .. } if host == "::1"
+        ));
+    }
+
+    #[test]
+    fn connection_info_rejects_bad_address() {
+        let info = tcp_info(None, None);
+        assert!(matches!(build_connection_info(&info, "localhost", "http"), Err(KVAdminerError::InvalidInput(_))));
+        assert!(matches!(build_connection_info(&info, "", "6379"), Err(KVAdminerError::InvalidInput(_))));
+    }
+
+    fn timeouts() -> RedisTimeouts {
+        RedisTimeouts { connect: Duration::from_secs(2), command: Duration::from_secs(2) }
+    }
+
+    async fn ping(info: &RedisInfo) -> Result<String, KVAdminerError> {
+        let client = create_redis_client(info, &timeouts())?;
+        let mut con = client.get_connection(&timeouts()).await?;
+        Ok(redis::cmd("PING").query_async(&mut con).await?)
+    }
+
+    /// Starts a TLS-only server with freshly generated certificates.
+    fn start_tls_server(extra_args: &[&str]) -> (TestServer, u16) {
+        let dir = test_server::scratch_dir();
+        test_server::write_certificates(&dir);
+        let port = test_server::free_port();
+        let file = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        let mut args = vec![
+            "--tls-port".to_string(),
+            port.to_string(),
+            "--tls-cert-file".to_string(),
+            file("server.crt"),
+            "--tls-key-file".to_string(),
+            file("server.key"),
+            "--tls-ca-cert-file".to_string(),
+            file("ca.crt"),
+        ];
+        args.extend(extra_args.iter().map(|arg| arg.to_string()));
+        (TestServer::start(dir, &Listen::Tcp(port), &args), port)
+    }
+
+    fn tls_info(server: &TestServer, port: u16) -> RedisInfo {
+        RedisInfo {
+            host: "localhost".to_string(),
+            port: port.to_string(),
+            tls: Some(true),
+            tls_ca_cert: Some(test_server::read(&server.dir, "ca.crt")),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server with TLS and openssl"]
+    async fn tls_connects_with_custom_ca() {
+        let (server, port) = start_tls_server(&["--tls-auth-clients", "no"]);
+        assert_eq!(ping(&tls_info(&server, port)).await.unwrap(), "PONG");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server with TLS and openssl"]
+    async fn tls_rejects_untrusted_server() {
+        let (server, port) = start_tls_server(&["--tls-auth-clients", "no"]);
+        let info = RedisInfo { tls_ca_cert: None, ..tls_info(&server, port) };
+        assert!(ping(&info).await.is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server with TLS and openssl"]
+    async fn tls_insecure_skips_verification() {
+        let (server, port) = start_tls_server(&["--tls-auth-clients", "no"]);
+        let info = RedisInfo { tls_ca_cert: None, tls_insecure: Some(true), ..tls_info(&server, port) };
+        assert_eq!(ping(&info).await.unwrap(), "PONG");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server with TLS and openssl"]
+    async fn tls_presents_client_certificate() {
+        let (server, port) = start_tls_server(&["--tls-auth-clients", "yes"]);
+        assert!(ping(&tls_info(&server, port)).await.is_err());
+        let info = RedisInfo {
+            tls_client_cert: Some(test_server::read(&server.dir, "client.crt")),
+            tls_client_key: Some(test_server::read(&server.dir, "client.key")),
+            ..tls_info(&server, port)
+        };
+        assert_eq!(ping(&info).await.unwrap(), "PONG");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server with TLS and openssl"]
+    async fn tls_authenticates_with_password_only() {
+        let password = "p@ss:w/rd#1";
+        let (server, port) = start_tls_server(&["--tls-auth-clients", "no", "--requirepass", password]);
+        assert!(ping(&tls_info(&server, port)).await.is_err());
+        let info = RedisInfo { password: Some(password.to_string()), ..tls_info(&server, port) };
+        assert_eq!(ping(&info).await.unwrap(), "PONG");
+    }
+
+    #[test]
+    fn socket_path_strips_url_schemes() {
+        assert_eq!(socket_path("/run/redis/redis.sock"), "/run/redis/redis.sock");
+        assert_eq!(socket_path("unix:///run/redis/redis.sock"), "/run/redis/redis.sock");
+        assert_eq!(socket_path("redis+unix:///run/redis/redis.sock"), "/run/redis/redis.sock");
+    }
+
+    #[test]
+    fn describe_connection_uses_socket_path() {
+        let info = RedisInfo {
+            socket: Some("unix:///run/redis/redis.sock".to_string()),
+            username: Some("".to_string()),
+            password: Some("secret".to_string()),
+            db: Some(3),
+            ..Default::default()
+        };
+        let summary = describe_connection(&info);
+        assert_eq!(summary.mode, ConnectionMode::Standalone);
+        assert_eq!(summary.address, "/run/redis/redis.sock");
+        assert_eq!(summary.username, None);
+        assert_eq!(summary.db, 3);
+    }
+
+    #[test]
+    fn describe_connection_ignores_blank_socket() {
+        let info = RedisInfo { socket: Some(" ".to_string()), username: Some("ops".to_string()), ..tcp_info(None, None) };
+        let summary = describe_connection(&info);
+        assert_eq!(summary.address, "localhost:6379");
+        assert_eq!(summary.username.as_deref(), Some("ops"));
+    }
+
+    #[test]
+    fn unix_socket_rejects_tls_and_cluster() {
+        let info = RedisInfo { socket: Some("/tmp/redis.sock".to_string()), tls: Some(true), ..Default::default() };
+        assert!(matches!(create_redis_client(&info, &timeouts()), Err(KVAdminerError::InvalidInput(_))));
+        let info = RedisInfo { socket: Some("/tmp/redis.sock".to_string()), mode: Some(ConnectionMode::Cluster), ..Default::default() };
+        assert!(matches!(create_redis_client(&info, &timeouts()), Err(KVAdminerError::Unsupported(_))));
+    }
+
+    fn start_unix_server(extra_args: &[&str]) -> (TestServer, String) {
+        let dir = test_server::scratch_dir();
+        let socket = dir.join("redis.sock");
+        let args: Vec<String> = extra_args.iter().map(|arg| arg.to_string()).collect();
+        let server = TestServer::start(dir, &Listen::Unix(socket.clone()), &args);
+        (server, socket.to_string_lossy().into_owned())
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server"]
+    async fn unix_socket_connects_by_path_and_url() {
+        let (_server, socket) = start_unix_server(&[]);
+        for socket in [socket.clone(), format!("unix://{}", socket), format!("redis+unix://{}", socket)] {
+            let info = RedisInfo { socket: Some(socket), ..Default::default() };
+            assert_eq!(ping(&info).await.unwrap(), "PONG");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs redis-server"]
+    async fn unix_socket_selects_database_and_authenticates() {
+        let (_server, socket) = start_unix_server(&["--requirepass", "p@ss:w/rd#1"]);
+        let info = RedisInfo { socket: Some(socket), db: Some(1), ..Default::default() };
+        assert!(ping(&info).await.is_err());
+
+        let info = RedisInfo { password: Some("p@ss:w/rd#1".to_string()), ..info };
+        let client = create_redis_client(&info, &timeouts()).unwrap();
+        assert_eq!(client.db(), 1);
+        let mut con = client.get_connection(&timeouts()).await.unwrap();
+        let key = RedisKey(b"kvadminer:test".to_vec());
+        let _: () = con.set(&key, "socket").await.unwrap();
+        let value: Option<String> = con.get(&key).await.unwrap();
+        assert_eq!(value.as_deref(), Some("socket"));
+    }
+
+    #[test]
+    fn bytes_serialize_text_and_binary_views() {
+        let text = serde_json::to_value(RedisBytes(b"hi".to_vec())).unwrap();
+        assert_eq!(text, serde_json::json!({"encoding": "utf8", "text": "hi", "hex": "6869", "base64": "aGk="}));
+
+        let binary = serde_json::to_value(RedisBytes(vec![0xff, 0x00])).unwrap();
+        assert_eq!(binary, serde_json::json!({"encoding": "binary", "text": null, "hex": "ff00", "base64": "/wA="}));
+    }
+
+    #[test]
+    fn bytes_round_trip_through_json() {
+        for bytes in [b"hello".to_vec(), vec![0xff, 0x00, 0x80], vec![]] {
+            let json = serde_json::to_string(&RedisBytes(bytes.clone())).unwrap();
+            assert_eq!(serde_json::from_str::<RedisBytes>(&json).unwrap(), RedisBytes(bytes));
+        }
+    }
+
+    #[test]
+    fn bytes_accept_plain_text_base64_or_hex() {
+        let parse = |json: &str| serde_json::from_str::<RedisBytes>(json);
+        assert_eq!(parse(r#""café""#).unwrap(), RedisBytes("café".as_bytes().to_vec()));
+        assert_eq!(parse(r#"{"base64": "/wA="}"#).unwrap(), RedisBytes(vec![0xff, 0x00]));
+        assert_eq!(parse(r#"{"hex": "ff00"}"#).unwrap(), RedisBytes(vec![0xff, 0x00]));
+        assert!(parse(r#"{"hex": "zz"}"#).is_err());
+        assert!(parse(r#"{"base64": "not base64!"}"#).is_err());
+        assert!(parse("{}").is_err());
+    }
+
+    #[test]
+    fn scan_position_parses_node_and_cursor() {
+        assert_eq!(ScanPosition::parse("").unwrap(), ScanPosition::default());
+        assert_eq!(ScanPosition::parse("42").unwrap(), ScanPosition { node: 0, cursor: 42 });
+        assert_eq!(ScanPosition::parse("2:17").unwrap(), ScanPosition { node: 2, cursor: 17 });
+
+        let position = ScanPosition { node: 3, cursor: 18446744073709551615 };
+        assert_eq!(ScanPosition::parse(&position.token()).unwrap(), position);
+    }
+
+    #[test]
+    fn scan_position_rejects_malformed_tokens() {
+        for token in ["abc", ":5", "1:", "-1:5", "1:-5", "1:2:3", " 5"] {
+            assert!(matches!(ScanPosition::parse(token), Err(KVAdminerError::InvalidInput(_))), "{token}");
+        }
+    }
+
+    #[test]
+    fn escape_glob_quotes_metacharacters() {
+        assert_eq!(escape_glob(b"user:1"), b"user:1");
+        assert_eq!(escape_glob(br"a*b?c[d]e\f"), br"a\*b\?c\[d\]e\\f");
+        assert_eq!(escape_glob(&[0xff, b'*']), [0xff, b'\\', b'*']);
+        assert!(validate_glob(std::str::from_utf8(&escape_glob(br"[*\?")).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn validate_glob_rejects_dangling_escapes_and_classes() {
+        for pattern in ["*", "user:*", "h?llo", "h[ae]llo", r"h[^\]]llo", r"a\*b", r"a\\", ""] {
+            assert!(validate_glob(pattern).is_ok(), "{pattern}");
+        }
+        for pattern in [r"abc\", r"a\\\", "h[ae", r"h[a\]", "[", "*[abc"] {
+            assert!(matches!(validate_glob(pattern), Err(KVAdminerError::InvalidInput(_))), "{pattern}");
+        }
+    }
+
+    fn stream_entry(id: &str) -> StreamEntry {
+        let field = HashField { field: RedisBytes(b"f".to_vec()), value: RedisBytes(b"v".to_vec()) };
+        StreamEntry { id: id.to_string(), fields: vec![field] }
+    }
+
+    #[test]
+    fn stream_ids_must_increase() {
+        let entries = |ids: &[&str]| ids.iter().map(|id| stream_entry(id)).collect::<Vec<_>>();
+        assert!(validate_stream_ids(&[]).is_ok());
+        assert!(validate_stream_ids(&entries(&["1-0", "1-1", "2-0", "*", "*"])).is_ok());
+        assert!(validate_stream_ids(&entries(&["0-1"])).is_ok());
+        for ids in [&["0-0"][..], &["2-0", "1-5"], &["1-1", "1-1"], &["*", "5-0"], &["1"], &["1-x"], &["-1-0"]] {
+            assert!(matches!(validate_stream_ids(&entries(ids)), Err(KVAdminerError::InvalidInput(_))), "{ids:?}");
+        }
+    }
+
+    #[test]
+    fn stream_entries_need_fields() {
+        let empty = StreamEntry { id: "1-0".to_string(), fields: vec![] };
+        assert!(matches!(validate_stream_ids(&[stream_entry("1-0"), empty]), Err(KVAdminerError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn zrange_defaults_to_the_whole_set() {
+        let bounds = |by| {
+            let range = ZRange::new(by, None, None, false).unwrap();
+            (range.min, range.max)
+        };
+        assert_eq!(bounds(ZRangeBy::Rank), ("0".to_string(), "-1".to_string()));
+        assert_eq!(bounds(ZRangeBy::Score), ("-inf".to_string(), "+inf".to_string()));
+        assert_eq!(bounds(ZRangeBy::Lex), ("-".to_string(), "+".to_string()));
+    }
+
+    #[test]
+    fn zrange_validates_bounds_by_kind() {
+        let range = |by, min: &str, max: &str| ZRange::new(by, Some(min.to_string()), Some(max.to_string()), false);
+        assert!(range(ZRangeBy::Rank, "-10", "5").is_ok());
+        assert!(range(ZRangeBy::Score, "(1.5", "inf").is_ok());
+        assert!(range(ZRangeBy::Score, "-inf", "(2e3").is_ok());
+        assert!(range(ZRangeBy::Lex, "[a", "(b").is_ok());
+        assert!(range(ZRangeBy::Lex, "-", "+").is_ok());
+
+        for (by, min, max) in [
+            (ZRangeBy::Rank, "1.5", "2"),
+            (ZRangeBy::Rank, "0", "(3"),
+            (ZRangeBy::Score, "abc", "1"),
+            (ZRangeBy::Score, "0", "nan"),
+            (ZRangeBy::Score, "((1", "2"),
+            (ZRangeBy::Lex, "a", "+"),
+            (ZRangeBy::Lex, "-", ""),
+        ] {
+            assert!(matches!(range(by, min, max), Err(KVAdminerError::InvalidInput(_))), "{by:?} {min} {max}");
+        }
+    }
+
+    #[test]
+    fn new_stream_ids_follow_xadd_rules() {
+        for id in ["*", "1700000000000-*", "0-*", "0-1", "1700000000000-5"] {
+            assert!(validate_new_stream_id(id).is_ok(), "{id}");
+        }
+        for id in ["", "0-0", "-*", "x-*", "1-x", "1700000000000", "1-2-3", "1-*-*"] {
+            assert!(matches!(validate_new_stream_id(id), Err(KVAdminerError::InvalidInput(_))), "{id}");
+        }
+        assert!(require_stream_id("5-0").is_ok());
+        assert!(require_stream_id("*").is_err());
+    }
 }
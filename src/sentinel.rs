@@ -0,0 +1,236 @@
+use crate::errors::KVAdminerError;
+use crate::redis_ops::{client_for_address, RedisTimeouts};
+use log::{info, warn};
+use redis::aio::{ConnectionLike, MultiplexedConnection};
+use redis::ErrorKind;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+// How long the resolved node is trusted before its ROLE is checked again; a connection or READONLY
+// error on it triggers the check on the next request
+const ROLE_CHECK_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// Which node of a Sentinel-monitored group connections should go to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SentinelRole {
+    Primary,
+    Replica,
+}
+
+/// The node a `SentinelClient` resolved, with its connection.
+struct ResolvedNode {
+    client: redis::Client,
+    connection: MultiplexedConnection,
+    checked: Option<Instant>, // Last time its ROLE was confirmed, `None` after an error hinting at a failover
+}
+
+type SharedNode = Arc<Mutex<Option<ResolvedNode>>>;
+
+fn lock(resolved: &SharedNode) -> MutexGuard<'_, Option<ResolvedNode>> {
+    resolved.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Resolves the current primary (or a replica) of a master group through its sentinels.
+///
+/// The resolved node and its multiplexed connection are cached and shared by all clones of the
+/// client, so a session keeps talking to the same node until it becomes unreachable or changes
+/// role after a failover, at which point the sentinels are asked again. The role is only checked
+/// every `ROLE_CHECK_MAX_AGE`, or after a command failed with a connection or READONLY error.
+#[derive(Clone)]
+pub struct SentinelClient {
+    sentinels: Vec<redis::Client>,
+    master_name: String,
+    role: SentinelRole,
+    node_template: redis::Client,
+    resolved: SharedNode,
+}
+
+/// A connection to the node resolved by a `SentinelClient`. Connection and READONLY errors mark
+/// the node for a ROLE check, so the next request notices a failover.
+#[derive(Clone)]
+pub struct SentinelConnection {
+    connection: MultiplexedConnection,
+    resolved: SharedNode,
+}
+
+impl SentinelConnection {
+    fn check_error<T>(&self, result: &redis::RedisResult<T>) {
+        if let Err(err) = result {
+            if err.is_io_error() || err.is_connection_dropped() || err.kind() == ErrorKind::ReadOnly {
+                if let Some(node) = lock(&self.resolved).as_mut() {
+                    node.checked = None;
+                }
+            }
+        }
+    }
+}
+
+impl ConnectionLike for SentinelConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a redis::Cmd) -> redis::RedisFuture<'a, redis::Value> {
+        Box::pin(async move {
+            let result = self.connection.req_packed_command(cmd).await;
+            self.check_error(&result);
+            result
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> redis::RedisFuture<'a, Vec<redis::Value>> {
+        Box::pin(async move {
+            let result = self.connection.req_packed_commands(cmd, offset, count).await;
+            self.check_error(&result);
+            result
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.connection.get_db()
+    }
+}
+
+impl SentinelClient {
+    pub fn new(
+        sentinels: Vec<redis::Client>,
+        master_name: String,
+        role: SentinelRole,
+        node_template: redis::Client,
+    ) -> SentinelClient {
+        SentinelClient {
+            sentinels,
+            master_name,
+            role,
+            node_template,
+            resolved: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn db(&self) -> i64 {
+        self.node_template.get_connection_info().redis.db
+    }
+
+    /// Returns a client for the same master group, pointed at another logical database.
+    pub fn with_database(&self, db: i64) -> Result<SentinelClient, KVAdminerError> {
+        let mut connection_info = self.node_template.get_connection_info().clone();
+        connection_info.redis.db = db;
+        let node_template = redis::Client::open(connection_info).map_err(KVAdminerError::from)?;
+        Ok(SentinelClient::new(self.sentinels.clone(), self.master_name.clone(), self.role, node_template))
+    }
+
+    /// Address of the node currently in use, if it has been resolved already.
+    pub fn current_address(&self) -> Option<String> {
+        lock(&self.resolved).as_ref().map(|node| node.client.get_connection_info().addr.to_string())
+    }
+
+    pub async fn get_connection(&self, timeouts: &RedisTimeouts) -> Result<SentinelConnection, KVAdminerError> {
+        let cached = lock(&self.resolved).as_ref().map(|node| (node.connection.clone(), node.checked));
+        if let Some((mut con, checked)) = cached {
+            if checked.is_some_and(|checked| checked.elapsed() < ROLE_CHECK_MAX_AGE) {
+                return Ok(self.connection(con));
+            }
+            match self.role_of(&mut con).await {
+                Ok(role) if role == self.expected_role() => {
+                    if let Some(node) = lock(&self.resolved).as_mut() {
+                        node.checked = Some(Instant::now());
+                    }
+                    return Ok(self.connection(con));
+                }
+                Ok(role) => warn!("Node for master {} is now a {}, re-resolving through sentinels", self.master_name, role),
+                Err(err) => warn!("Node for master {} is unreachable ({}), re-resolving through sentinels", self.master_name, err),
+            }
+        }
+
+        let client = self.resolve(timeouts).await?;
+        let con = client
+            .get_multiplexed_async_connection_with_timeouts(timeouts.command, timeouts.connect)
+            .await?;
+        *lock(&self.resolved) = Some(ResolvedNode { client, connection: con.clone(), checked: Some(Instant::now()) });
+        Ok(self.connection(con))
+    }
+
+    fn connection(&self, connection: MultiplexedConnection) -> SentinelConnection {
+        SentinelConnection { connection, resolved: self.resolved.clone() }
+    }
+
+    fn expected_role(&self) -> &'static str {
+        match self.role {
+            SentinelRole::Primary => "master",
+            SentinelRole::Replica => "slave",
+        }
+    }
+
+    async fn role_of(&self, con: &mut MultiplexedConnection) -> Result<String, KVAdminerError> {
+        let reply: Vec<redis::Value> = redis::cmd("ROLE").query_async(con).await?;
+        match reply.first() {
+            Some(redis::Value::Data(data)) => Ok(String::from_utf8_lossy(data).to_string()),
+            _ => Err(redis::RedisError::from((ErrorKind::TypeError, "Unexpected ROLE reply")).into()),
+        }
+    }
+
+    /// Asks each sentinel in turn for the node to use, returning the first answer.
+    async fn resolve(&self, timeouts: &RedisTimeouts) -> Result<redis::Client, KVAdminerError> {
+        let mut last_err = KVAdminerError::from(redis::RedisError::from((ErrorKind::EmptySentinelList, "No sentinel configured")));
+        for sentinel in &self.sentinels {
+            let address = match sentinel
+                .get_multiplexed_async_connection_with_timeouts(timeouts.command, timeouts.connect)
+                .await
+            {
+                Ok(mut con) => match self.role {
+                    SentinelRole::Primary => self.primary_address(&mut con).await,
+                    SentinelRole::Replica => self.replica_address(&mut con).await,
+                },
+                Err(err) => Err(KVAdminerError::from(err)),
+            };
+            match address {
+                Ok((host, port)) => {
+                    info!("Sentinel resolved master {} to {}:{}", self.master_name, host, port);
+                    return client_for_address(&self.node_template, &host, port);
+                }
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+
+    async fn primary_address(&self, con: &mut MultiplexedConnection) -> Result<(String, u16), KVAdminerError> {
+        let address: Option<(String, u16)> = redis::cmd("SENTINEL")
+            .arg("get-master-addr-by-name")
+            .arg(&self.master_name)
+            .query_async(con)
+            .await?;
+        address.ok_or_else(|| {
+            redis::RedisError::from((ErrorKind::MasterNameNotFoundBySentinel, "Sentinel does not know master", self.master_name.clone())).into()
+        })
+    }
+
+    async fn replica_address(&self, con: &mut MultiplexedConnection) -> Result<(String, u16), KVAdminerError> {
+        let replicas: Vec<HashMap<String, String>> = redis::cmd("SENTINEL")
+            .arg("replicas")
+            .arg(&self.master_name)
+            .query_async(con)
+            .await?;
+        replicas
+            .iter()
+            .filter(|replica| {
+                let flags = replica.get("flags").map(String::as_str).unwrap_or("");
+                !flags.split(',').any(|flag| matches!(flag, "s_down" | "o_down" | "disconnected"))
+            })
+            .find_map(|replica| {
+                let host = replica.get("ip")?.clone();
+                let port = replica.get("port")?.parse().ok()?;
+                Some((host, port))
+            })
+            .ok_or_else(|| {
+                redis::RedisError::from((
+                    ErrorKind::NoValidReplicasFoundBySentinel,
+                    "No healthy replica available for master",
+                    self.master_name.clone(),
+                ))
+                .into()
+            })
+    }
+}
@@ -1,36 +1,86 @@
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::OnceLock;
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 use std::sync::Arc;
 use log::info;
 use actix_web::{HttpRequest};
 use std::time::{Duration, Instant};
+use crate::cluster::ClusterPrimaries;
+use crate::profiles::ProfileStore;
+use crate::redis_ops::{ConnectionSummary, KeyFilter, RedisClient, RedisConnection, RedisTimeouts};
 
 #[derive(Clone)]
 pub struct AppState {
     pub connections: Arc<Mutex<HashMap<String, SessionData>>>,
     pub session_timeout: Duration,
+    pub redis_timeouts: RedisTimeouts,
+    pub profiles: Arc<Mutex<ProfileStore>>,
 }
 
 pub struct SessionData {
-    pub client: redis::Client,
+    pub connections: HashMap<String, ConnectionData>, // Keyed by connection id
+    pub default_connection: Option<String>, // Used by requests that do not name a connection
     pub last_active: Instant,
 }
 
+/// One Redis connection opened by a session through `/connect`.
+pub struct ConnectionData {
+    pub name: String,
+    pub client: RedisClient,
+    pub connection: RedisConnection,
+    pub summary: ConnectionSummary,
+    pub key_counts: KeyCounts,
+    pub primaries: ClusterPrimaries, // Cluster mode only
+}
+
+/// Approximate number of keys matching a pattern, counted in the background for cursor based listings.
+#[derive(Clone, Copy)]
+pub struct KeyCount {
+    pub total: u64,
+    pub at: Instant,
+}
+
+/// What a key count covers: database, SCAN pattern and filter.
+pub type KeyCountTarget = (i64, Vec<u8>, KeyFilter);
+
+/// Key counts of a connection. At most one count runs at a time, a count for another target
+/// aborts it, and so does closing the connection.
+#[derive(Default)]
+pub struct KeyCountState {
+    pub counted: HashMap<KeyCountTarget, KeyCount>,
+    pub running: Option<(KeyCountTarget, AbortHandle)>,
+}
+
+impl Drop for KeyCountState {
+    fn drop(&mut self) {
+        if let Some((_, task)) = self.running.take() {
+            task.abort();
+        }
+    }
+}
+
+pub type KeyCounts = Arc<Mutex<KeyCountState>>;
+
+/// Short tag standing in for a session in log messages. The session id alone unlocks the stored
+/// Redis connections, so it is never logged; the tag is a keyed hash that changes on every restart.
+pub fn session_tag(session_id: &str) -> String {
+    static KEY: OnceLock<RandomState> = OnceLock::new();
+    format!("{:08x}", KEY.get_or_init(RandomState::new).hash_one(session_id) as u32)
+}
+
 pub fn generate_session_id() -> String {
     let session_id = Uuid::new_v4().to_string();
-    info!("Generated new session ID: {}", session_id);
+    info!("Created session {}", session_tag(&session_id));
     session_id
 }
 
 pub fn get_or_create_session_id(req: &HttpRequest) -> String {
-    if let Some(cookie) = req.cookie("session_id") {
-        info!("Found existing session ID: {}", cookie.value());
-        cookie.value().to_string()
-    } else {
-        let session_id = generate_session_id();
-        info!("Creating new session ID: {}", session_id);
-        session_id
+    match req.cookie("session_id") {
+        Some(cookie) => cookie.value().to_string(),
+        None => generate_session_id(),
     }
 }
 
@@ -40,8 +90,23 @@ pub async fn cleanup_expired_sessions(state: Arc<AppState>) {
     connections.retain(|session_id, session_data| {
         let is_active = now.duration_since(session_data.last_active) <= state.session_timeout;
         if !is_active {
-            info!("Session expired and removed: {}", session_id);
+            info!("Session {} expired and was removed", session_tag(session_id));
         }
         is_active
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_tag_is_stable_and_hides_the_id() {
+        let session_id = "3f2b8c1e-9d4a-4b7e-8f60-1a2b3c4d5e6f";
+        let tag = session_tag(session_id);
+        assert_eq!(tag, session_tag(session_id));
+        assert_eq!(tag.len(), 8);
+        assert!(!session_id.contains(&tag));
+        assert_ne!(tag, session_tag("0c9e7a52-2d1f-4e3b-9a8c-7b6d5e4f3a21"));
+    }
+}
@@ -0,0 +1,124 @@
+//! Throwaway `redis-server` processes for tests that need a real server.
+//!
+//! The binary is taken from `REDIS_SERVER` (default `redis-server` on the `PATH`) and certificates are
+//! generated with `OPENSSL` (default `openssl`). Tests using them are `#[ignore]`d and run with
+//! `cargo test -- --ignored`; a missing binary, or a server that cannot start (e.g. a build without
+//! TLS), then fails the test instead of letting it pass unchecked.
+
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// Where the server accepts connections.
+pub enum Listen {
+    Tcp(u16),
+    Unix(PathBuf),
+}
+
+pub struct TestServer {
+    child: Child,
+    pub dir: PathBuf, // Scratch directory holding the socket, certificates and dump files
+}
+
+impl TestServer {
+    pub fn start(dir: PathBuf, listen: &Listen, args: &[String]) -> TestServer {
+        let binary = std::env::var("REDIS_SERVER").unwrap_or_else(|_| "redis-server".to_string());
+        let mut command = Command::new(&binary);
+        command.arg("--save").arg("").arg("--appendonly").arg("no").arg("--dir").arg(&dir);
+        match listen {
+            Listen::Tcp(_) if args.iter().any(|arg| arg == "--tls-port") => command.arg("--port").arg("0"),
+            Listen::Tcp(port) => command.arg("--port").arg(port.to_string()).arg("--bind").arg("127.0.0.1"),
+            Listen::Unix(path) => command.arg("--port").arg("0").arg("--unixsocket").arg(path),
+        };
+        let child = command.args(args).stdout(Stdio::null()).stderr(Stdio::null()).spawn();
+        let mut server = match child {
+            Ok(child) => TestServer { child, dir },
+            Err(err) => panic!("cannot run {} (set REDIS_SERVER): {}", binary, err),
+        };
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Ok(Some(status)) = server.child.try_wait() {
+                panic!("{} exited with {} (args: {:?})", binary, status, args);
+            }
+            let ready = match listen {
+                Listen::Tcp(port) => TcpStream::connect(("127.0.0.1", *port)).is_ok(),
+                Listen::Unix(path) => std::os::unix::net::UnixStream::connect(path).is_ok(),
+            };
+            if ready {
+                return server;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("{} did not start listening in time", binary);
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A fresh, empty directory under the system temp dir.
+pub fn scratch_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "kvadminer-test-{}-{}",
+        std::process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+/// A local port that was free a moment ago.
+pub fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").and_then(|listener| listener.local_addr()).expect("bind a free port").port()
+}
+
+fn openssl(dir: &Path, args: &[&str]) {
+    let binary = std::env::var("OPENSSL").unwrap_or_else(|_| "openssl".to_string());
+    match Command::new(&binary).args(args).current_dir(dir).stdout(Stdio::null()).stderr(Stdio::null()).status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => panic!("{} {:?} failed with {}", binary, args, status),
+        Err(err) => panic!("cannot run {} (set OPENSSL): {}", binary, err),
+    }
+}
+
+/// Writes `ca.crt`, `server.crt`/`server.key` (valid for `localhost` and `127.0.0.1`) and
+/// `client.crt`/`client.key` into `dir`, all signed by the same throwaway CA.
+pub fn write_certificates(dir: &Path) {
+    let ec = ["-newkey", "ec", "-pkeyopt", "ec_paramgen_curve:prime256v1", "-nodes"];
+    let ca = [
+        &["req", "-x509", "-days", "1", "-subj", "/CN=kvadminer test CA", "-keyout", "ca.key", "-out", "ca.crt"][..],
+        &ec[..],
+        &["-addext", "basicConstraints=critical,CA:TRUE", "-addext", "keyUsage=critical,keyCertSign,cRLSign"][..],
+    ]
+    .concat();
+    std::fs::write(dir.join("server.ext"), "subjectAltName=DNS:localhost,IP:127.0.0.1\nextendedKeyUsage=serverAuth\n").expect("write server.ext");
+    std::fs::write(dir.join("client.ext"), "extendedKeyUsage=clientAuth\n").expect("write client.ext");
+    openssl(dir, &ca);
+    for name in ["server", "client"] {
+        let (key, csr, crt, ext) = (format!("{name}.key"), format!("{name}.csr"), format!("{name}.crt"), format!("{name}.ext"));
+        let request = [&["req", "-new", "-subj", "/CN=localhost", "-keyout", &key, "-out", &csr][..], &ec[..]].concat();
+        openssl(dir, &request);
+        openssl(
+            dir,
+            &[
+                "x509", "-req", "-days", "1", "-in", &csr, "-CA", "ca.crt", "-CAkey", "ca.key", "-CAcreateserial",
+                "-extfile", &ext, "-out", &crt,
+            ],
+        );
+    }
+}
+
+pub fn read(dir: &Path, name: &str) -> String {
+    std::fs::read_to_string(dir.join(name)).expect("read generated file")
+}
@@ -1,29 +1,309 @@
 document.addEventListener('DOMContentLoaded', function () {
     const infoBar = document.getElementById('info-bar');
 
-    const host = localStorage.getItem('redis_host');
-    const port = localStorage.getItem('redis_port');
-    const username = localStorage.getItem('redis_user');
-    const password = localStorage.getItem('redis_password');
+    // Credentials used to live in localStorage and were sent with every request; drop leftovers
+    ['redis_password', 'redis_sentinel_password', 'redis_tls_client_key'].forEach(item => localStorage.removeItem(item));
 
-    if (host && port) {
-        infoBar.textContent = `Connected to ${host}:${port} as ${username ? username : 'anonymous'}`;
-    } else {
-        infoBar.textContent = `Not connected`;
+    function showAlert(message, type = 'error') {
+        const alertDiv = document.createElement('div');
+        alertDiv.className = `alert ${type}`;
+        // Messages carry key names, member bytes and Redis errors, so they are set as text, never as HTML
+        alertDiv.textContent = message;
+        const closeButton = document.createElement('span');
+        closeButton.className = 'closebtn';
+        closeButton.textContent = '\u00d7';
+        closeButton.addEventListener('click', () => alertDiv.style.display = 'none');
+        alertDiv.appendChild(closeButton);
+        document.body.prepend(alertDiv);
+        setTimeout(() => alertDiv.style.display = 'none', 3000);
     }
 
-    document.getElementById('connect-form').addEventListener('submit', function (event) {
+    // Errors come back as a JSON envelope ({ code, message, redis_kind, retryable })
+    async function readError(response) {
+        const body = await response.text();
+        try {
+            return JSON.parse(body).message || body;
+        } catch (e) {
+            return body;
+        }
+    }
+
+    fetch('/connections')
+        .then(response => response.ok ? response.json() : [])
+        .then(connections => {
+            if (connections.length) {
+                infoBar.textContent = `Connected to ${connections.map(connection => connection.name).join(', ')}`;
+            } else {
+                infoBar.textContent = `Not connected`;
+            }
+        });
+
+    const modeSelect = document.getElementById('mode');
+    const addressOptions = document.getElementById('address-options');
+    const sentinelOptions = document.getElementById('sentinel-options');
+    const tlsCheckbox = document.getElementById('tls');
+    const tlsOptions = document.getElementById('tls-options');
+
+    // Only non-secret fields are remembered to prefill the form
+    modeSelect.value = localStorage.getItem('redis_mode') || 'standalone';
+    document.getElementById('host').value = localStorage.getItem('redis_host') || '';
+    document.getElementById('port').value = localStorage.getItem('redis_port') || '';
+    document.getElementById('username').value = localStorage.getItem('redis_user') || '';
+    document.getElementById('socket').value = localStorage.getItem('redis_socket') || '';
+    document.getElementById('sentinels').value = localStorage.getItem('redis_sentinels') || '';
+    document.getElementById('master-name').value = localStorage.getItem('redis_master_name') || '';
+    document.getElementById('read-from-replica').checked = localStorage.getItem('redis_read_from_replica') === 'true';
+    document.getElementById('db').value = localStorage.getItem('redis_db') || 0;
+    tlsCheckbox.checked = localStorage.getItem('redis_tls') === 'true';
+    tlsOptions.disabled = !tlsCheckbox.checked;
+    document.getElementById('tls-insecure').checked = localStorage.getItem('redis_tls_insecure') === 'true';
+    document.getElementById('tls-ca-cert').value = localStorage.getItem('redis_tls_ca_cert') || '';
+    document.getElementById('tls-client-cert').value = localStorage.getItem('redis_tls_client_cert') || '';
+
+    // Sentinel mode discovers the data node itself, so host and port are not asked for
+    function updateModeOptions() {
+        const isSentinel = modeSelect.value === 'sentinel';
+        sentinelOptions.disabled = !isSentinel;
+        addressOptions.disabled = isSentinel;
+    }
+    updateModeOptions();
+    modeSelect.addEventListener('change', updateModeOptions);
+
+    // Host and port are only required when no unix socket is given
+    const socketInput = document.getElementById('socket');
+    function updateAddressOptions() {
+        const hasSocket = socketInput.value.trim() !== '';
+        document.getElementById('host').required = !hasSocket;
+        document.getElementById('port').required = !hasSocket;
+    }
+    updateAddressOptions();
+    socketInput.addEventListener('input', updateAddressOptions);
+
+    tlsCheckbox.addEventListener('change', () => {
+        tlsOptions.disabled = !tlsCheckbox.checked;
+    });
+
+    // Load PEM files picked from disk into the matching textarea
+    ['tls-ca-cert', 'tls-client-cert', 'tls-client-key'].forEach(id => {
+        document.getElementById(`${id}-file`).addEventListener('change', (event) => {
+            const file = event.target.files[0];
+            if (!file) {
+                return;
+            }
+            const reader = new FileReader();
+            reader.onload = () => document.getElementById(id).value = reader.result;
+            reader.readAsText(file);
+        });
+    });
+
+    function readForm() {
+        const mode = modeSelect.value;
+        const isSentinel = mode === 'sentinel';
+        const tls = tlsCheckbox.checked;
+        return {
+            mode,
+            host: isSentinel ? '' : document.getElementById('host').value,
+            port: isSentinel ? '' : document.getElementById('port').value,
+            socket: isSentinel ? '' : socketInput.value.trim(),
+            username: document.getElementById('username').value,
+            password: document.getElementById('password').value,
+            db: mode === 'cluster' ? 0 : parseInt(document.getElementById('db').value) || 0,
+            sentinels: isSentinel ? document.getElementById('sentinels').value : '',
+            master_name: isSentinel ? document.getElementById('master-name').value : '',
+            sentinel_password: isSentinel ? document.getElementById('sentinel-password').value : '',
+            read_from_replica: isSentinel && document.getElementById('read-from-replica').checked,
+            tls,
+            tls_insecure: tls && document.getElementById('tls-insecure').checked,
+            tls_ca_cert: tls ? document.getElementById('tls-ca-cert').value : '',
+            tls_client_cert: tls ? document.getElementById('tls-client-cert').value : '',
+            tls_client_key: tls ? document.getElementById('tls-client-key').value : ''
+        };
+    }
+
+    // Saved profiles never send their secrets back, so those fields are left empty
+    function fillForm(settings) {
+        modeSelect.value = settings.mode || 'standalone';
+        document.getElementById('host').value = settings.host || '';
+        document.getElementById('port').value = settings.port || '';
+        socketInput.value = settings.socket || '';
+        document.getElementById('username').value = settings.username || '';
+        document.getElementById('password').value = '';
+        document.getElementById('db').value = settings.db || 0;
+        document.getElementById('sentinels').value = settings.sentinels || '';
+        document.getElementById('master-name').value = settings.master_name || '';
+        document.getElementById('sentinel-password').value = '';
+        document.getElementById('read-from-replica').checked = !!settings.read_from_replica;
+        tlsCheckbox.checked = !!settings.tls;
+        tlsOptions.disabled = !tlsCheckbox.checked;
+        document.getElementById('tls-insecure').checked = !!settings.tls_insecure;
+        document.getElementById('tls-ca-cert').value = settings.tls_ca_cert || '';
+        document.getElementById('tls-client-cert').value = settings.tls_client_cert || '';
+        document.getElementById('tls-client-key').value = '';
+        updateModeOptions();
+        updateAddressOptions();
+    }
+
+    const profileSelect = document.getElementById('profile-select');
+    let profiles = [];
+
+    async function fetchProfiles(selected = null) {
+        const response = await fetch('/profiles');
+        if (!response.ok) {
+            showAlert('Failed to fetch connection profiles');
+            return;
+        }
+        profiles = await response.json();
+        profileSelect.innerHTML = '<option value="">-- new connection --</option>';
+        profiles.forEach(profile => {
+            const option = document.createElement('option');
+            option.value = profile.name;
+            option.textContent = `${profile.name} (${profile.summary.address})`;
+            profileSelect.appendChild(option);
+        });
+        profileSelect.value = selected || '';
+    }
+
+    profileSelect.addEventListener('change', () => {
+        const profile = profiles.find(p => p.name === profileSelect.value);
+        document.getElementById('profile-name').value = profile ? profile.name : '';
+        if (profile) {
+            fillForm(profile.settings);
+        }
+    });
+
+    document.getElementById('profile-connect').addEventListener('click', async () => {
+        if (!profileSelect.value) {
+            showAlert('Select a profile first');
+            return;
+        }
+        const response = await fetch(`/profiles/${encodeURIComponent(profileSelect.value)}/connect`, { method: 'POST' });
+        if (response.ok) {
+            const connection = await response.json();
+            window.location.href = `/keys-management?connection=${encodeURIComponent(connection.id)}`;
+        } else {
+            const errorMessage = await readError(response);
+            showAlert(`Failed to connect: ${errorMessage}`);
+        }
+    });
+
+    document.getElementById('profile-delete').addEventListener('click', async () => {
+        if (!profileSelect.value) {
+            return;
+        }
+        const response = await fetch(`/profiles/${encodeURIComponent(profileSelect.value)}`, { method: 'DELETE' });
+        if (response.ok) {
+            showAlert('Profile deleted successfully', 'success');
+            fetchProfiles();
+        } else {
+            const errorMessage = await readError(response);
+            showAlert(`Failed to delete profile: ${errorMessage}`);
+        }
+    });
+
+    document.getElementById('profile-save').addEventListener('click', async () => {
+        const name = document.getElementById('profile-name').value.trim();
+        if (!name) {
+            showAlert('Enter a profile name');
+            return;
+        }
+        const exists = profiles.some(p => p.name === name);
+        const settings = readForm();
+        // Empty secrets keep the stored value on updates, unless they are to be forgotten
+        const clearSecrets = document.getElementById('profile-clear-secrets').checked
+            ? ['password', 'sentinel_password', 'tls_client_key'].filter(secret => !settings[secret])
+            : [];
+        const response = await fetch(exists ? `/profiles/${encodeURIComponent(name)}` : '/profiles', {
+            method: exists ? 'PUT' : 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify(exists ? { ...settings, clear_secrets: clearSecrets } : { name, ...settings })
+        });
+        if (response.ok) {
+            showAlert('Profile saved successfully', 'success');
+            fetchProfiles(name);
+        } else {
+            const errorMessage = await readError(response);
+            showAlert(`Failed to save profile: ${errorMessage}`);
+        }
+    });
+
+    const testResult = document.getElementById('test-result');
+
+    function showTestResult(result) {
+        if (!result.ok) {
+            testResult.className = 'test-result error';
+            testResult.textContent = `Connection failed: ${result.error}`;
+            return;
+        }
+        const server = result.server;
+        const modules = server.modules.length ? server.modules.join(', ') : 'none';
+        testResult.className = 'test-result success';
+        testResult.textContent = `Connection OK: Redis ${server.version || 'unknown version'} (${server.server_mode || server.mode}, ${server.role || 'unknown role'}), RESP${server.protocol}, modules: ${modules}, latency ${server.latency_ms.toFixed(2)} ms`;
+    }
+
+    async function testConnection(url, body) {
+        testResult.className = 'test-result';
+        testResult.textContent = 'Testing connection...';
+        const response = await fetch(url, {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: body ? JSON.stringify(body) : undefined
+        });
+        if (response.ok) {
+            showTestResult(await response.json());
+        } else {
+            const errorMessage = await readError(response);
+            showTestResult({ ok: false, error: errorMessage });
+        }
+    }
+
+    document.getElementById('test-connection').addEventListener('click', () => testConnection('/connection/test', readForm()));
+
+    document.getElementById('profile-test').addEventListener('click', () => {
+        if (!profileSelect.value) {
+            showAlert('Select a profile first');
+            return;
+        }
+        testConnection(`/profiles/${encodeURIComponent(profileSelect.value)}/test`);
+    });
+
+    fetchProfiles();
+
+    document.getElementById('connect-form').addEventListener('submit', async function (event) {
         event.preventDefault();
-        const host = document.getElementById('host').value;
-        const port = document.getElementById('port').value;
-        const username = document.getElementById('username').value;
-        const password = document.getElementById('password').value;
+        const connection = readForm();
+
+        const response = await fetch('/connect', {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ name: document.getElementById('connection-name').value, ...connection })
+        });
+        if (!response.ok) {
+            const errorMessage = await readError(response);
+            showAlert(`Failed to connect: ${errorMessage}`);
+            return;
+        }
 
-        localStorage.setItem('redis_host', host);
-        localStorage.setItem('redis_port', port);
-        localStorage.setItem('redis_user', username);
-        localStorage.setItem('redis_password', password);
+        localStorage.setItem('redis_mode', connection.mode);
+        localStorage.setItem('redis_host', connection.host);
+        localStorage.setItem('redis_port', connection.port);
+        localStorage.setItem('redis_socket', connection.socket);
+        localStorage.setItem('redis_user', connection.username);
+        localStorage.setItem('redis_db', connection.db);
+        localStorage.setItem('redis_sentinels', connection.sentinels);
+        localStorage.setItem('redis_master_name', connection.master_name);
+        localStorage.setItem('redis_read_from_replica', connection.read_from_replica);
+        localStorage.setItem('redis_tls', connection.tls);
+        localStorage.setItem('redis_tls_insecure', connection.tls_insecure);
+        localStorage.setItem('redis_tls_ca_cert', connection.tls_ca_cert);
+        localStorage.setItem('redis_tls_client_cert', connection.tls_client_cert);
 
-        window.location.href = '/keys-management';
+        const entry = await response.json();
+        window.location.href = `/keys-management?connection=${encodeURIComponent(entry.id)}`;
     });
 });
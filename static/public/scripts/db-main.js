@@ -1,75 +1,322 @@
 document.addEventListener('DOMContentLoaded', function () {
     const infoBar = document.getElementById('info-bar');
 
-    const host = localStorage.getItem('redis_host');
-    const port = localStorage.getItem('redis_port');
-    const username = localStorage.getItem('redis_user');
-    const password = localStorage.getItem('redis_password');
+    let summary = null;
 
-    if (host && port) {
-        infoBar.textContent = `Connected to ${host}:${port} as ${username ? username : 'anonymous'}`;
-    } else {
-        infoBar.textContent = `Not connected`;
+    // The connection browsed by this page, kept in the URL so each tab can show a different one
+    let connectionId = new URLSearchParams(window.location.search).get('connection');
+
+    function withConnection(path) {
+        if (!connectionId) {
+            return path;
+        }
+        const separator = path.includes('?') ? '&' : '?';
+        return `${path}${separator}connection=${encodeURIComponent(connectionId)}`;
+    }
+
+    function updateInfoBar(node = null) {
+        if (!summary) {
+            infoBar.textContent = `Not connected`;
+            return;
+        }
+        const user = summary.username ? summary.username : 'anonymous';
+        const target = node ? `${summary.address} (${node})` : summary.address;
+        const via = summary.mode === 'sentinel' ? ' via sentinel' : '';
+        infoBar.textContent = `${summary.name}: connected to ${target}${via}/${summary.db}${summary.tls ? ' (TLS)' : ''} as ${user}`;
     }
 
+    async function fetchConnection() {
+        const response = await fetch(withConnection('/connection'));
+        if (response.status === 401) {
+            window.location.href = '/';
+            return;
+        }
+        summary = response.ok ? await response.json() : null;
+        if (summary && !connectionId) {
+            connectionId = summary.id;
+        }
+        updateInfoBar();
+    }
+
+    const connectionSelect = document.getElementById('connection-select');
+
+    async function fetchConnections() {
+        const response = await fetch('/connections');
+        if (!response.ok) {
+            return;
+        }
+        const connections = await response.json();
+        connectionSelect.innerHTML = '';
+        connections.forEach(connection => {
+            const option = document.createElement('option');
+            option.value = connection.id;
+            option.textContent = connection.name;
+            connectionSelect.appendChild(option);
+        });
+        connectionSelect.value = connectionId;
+    }
+
+    connectionSelect.addEventListener('change', async () => {
+        connectionId = connectionSelect.value;
+        history.replaceState(null, '', `/keys-management?connection=${encodeURIComponent(connectionId)}`);
+        currentPage = 0;
+        await fetchConnection();
+        await fetchDatabases();
+        const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
+        fetchKeys(searchQuery).then(displayKeys);
+    });
+
+    document.getElementById('close-connection').addEventListener('click', async () => {
+        const response = await fetch(withConnection('/disconnect'), { method: 'POST' });
+        if (!response.ok) {
+            showAlert(`Failed to close connection: ${await readError(response)}`);
+            return;
+        }
+        const remaining = await fetch('/connections').then(r => r.ok ? r.json() : []);
+        window.location.href = remaining.length ? `/keys-management?connection=${encodeURIComponent(remaining[0].id)}` : '/';
+    });
+
     function showAlert(message, type = 'error') {
         const alertDiv = document.createElement('div');
         alertDiv.className = `alert ${type}`;
-        alertDiv.innerHTML = `${message}<span class="closebtn" onclick="this.parentElement.style.display='none';">&times;</span>`;
+        // Messages carry key names, member bytes and Redis errors, so they are set as text, never as HTML
+        alertDiv.textContent = message;
+        const closeButton = document.createElement('span');
+        closeButton.className = 'closebtn';
+        closeButton.textContent = '\u00d7';
+        closeButton.addEventListener('click', () => alertDiv.style.display = 'none');
+        alertDiv.appendChild(closeButton);
         document.body.prepend(alertDiv);
         setTimeout(() => alertDiv.style.display = 'none', 3000);
     }
 
+    // Errors come back as a JSON envelope ({ code, message, redis_kind, retryable })
+    async function readError(response) {
+        const body = await response.text();
+        try {
+            return JSON.parse(body).message || body;
+        } catch (e) {
+            return body;
+        }
+    }
+
     let currentPage = 0;
+    // Cursor token of every page reached so far; pages are read with SCAN so only these can be revisited
+    let cursors = [''];
+    // Counting keys takes a full SCAN unless every key matches, so it only happens on request
+    let countedTotal = null; // `{ scope, total }` of the last finished count
+    let countingScope = null; // Scope of the count being polled for, if any
+    let countPoll = null;
     const defaultPageSize = 10;
     const pageSizeDropdowns = document.querySelectorAll('[id^="page-size"]');
     pageSizeDropdowns.forEach(dropdown => dropdown.value = defaultPageSize);
 
+    // Type, TTL and size filters, leaving out the ones that are not set
+    function filterParams() {
+        const params = {};
+        const type = document.getElementById('filter-type').value;
+        const ttl = document.getElementById('filter-ttl').value;
+        const expiresWithin = parseInt(document.getElementById('filter-expires-within').value);
+        const minSize = parseFloat(document.getElementById('filter-min-size').value);
+        const maxSize = parseFloat(document.getElementById('filter-max-size').value);
+        if (type) {
+            params.type = type;
+        }
+        if (ttl === 'within') {
+            if (expiresWithin > 0) {
+                params.expires_within = expiresWithin;
+            }
+        } else if (ttl) {
+            params.ttl = ttl;
+        }
+        if (minSize >= 0) {
+            params.min_size = Math.round(minSize * 1024);
+        }
+        if (maxSize >= 0) {
+            params.max_size = Math.round(maxSize * 1024);
+        }
+        return params;
+    }
+
+    // Search and filter parameters shared by `/keys` and `/keys/count`
+    function searchParams(searchQuery) {
+        return {
+            search: searchQuery,
+            search_mode: document.getElementById('search-mode').value,
+            ...filterParams()
+        };
+    }
+
+    function currentSearchQuery() {
+        const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
+        return new URLSearchParams(searchParams(searchQuery)).toString();
+    }
+
     async function fetchKeys(searchQuery = '') {
         const pageSize = parseInt(pageSizeDropdowns[0].value) || defaultPageSize;
         const queryParams = new URLSearchParams({
-            host,
-            port,
-            username,
-            password,
-            page: currentPage,
+            cursor: cursors[currentPage],
             page_size: pageSize,
-            search: searchQuery
+            ...searchParams(searchQuery)
         }).toString();
-        const response = await fetch(`/keys?${queryParams}`);
+        const response = await fetch(withConnection(`/keys?${queryParams}`));
         if (!response.ok) {
-            showAlert('Failed to fetch keys');
-            return { keys: [], current_page: 0, total_pages: 0, total_keys: 0 };
+            showAlert(`Failed to fetch keys: ${await readError(response)}`);
+            return { keys: [], mode: summary ? summary.mode : 'standalone', cursor: null, approx_total: 0 };
         }
         return await response.json();
     }
 
+    function escapeHtml(text) {
+        const div = document.createElement('div');
+        div.textContent = text;
+        return div.innerHTML.replace(/"/g, '&quot;');
+    }
+
+    // Binary data is previewed as hex, valid UTF-8 as text
+    function previewBytes(bytes) {
+        return bytes.encoding === 'utf8' ? bytes.text : `0x${bytes.hex}`;
+    }
+
+    // One line preview of a typed value; collections are shown as JSON so members stay unambiguous.
+    // Strings, lists, sorted sets and hashes only hold their start; `size` tells whether there is more
+    function formatValue(value, size) {
+        const moreItems = shown => size > shown ? ` (${size} items)` : '';
+        switch (value.type) {
+            case 'String': {
+                const more = size > value.value.hex.length / 2 ? `... (${size} bytes)` : '';
+                return `${previewBytes(value.value)}${more}`;
+            }
+            case 'List':
+                return `${JSON.stringify(value.value.map(previewBytes))}${moreItems(value.value.length)}`;
+            case 'Set': {
+                // Only the first members are included; the size tells whether there are more
+                const { size, members } = value.value;
+                const more = size > members.length ? ` (${size} members)` : '';
+                return `${JSON.stringify(members.map(previewBytes))}${more}`;
+            }
+            case 'ZSet':
+                return `${JSON.stringify(value.value.map(({ member, score }) => [previewBytes(member), score]))}${moreItems(value.value.length)}`;
+            case 'Hash': {
+                const fields = Object.fromEntries(value.value.map(({ field, value }) => [previewBytes(field), previewBytes(value)]));
+                return `${JSON.stringify(fields)}${moreItems(value.value.length)}`;
+            }
+            case 'Stream':
+                return `(${value.value.length} entries)`;
+            default:
+                return `(${value.value})`;
+        }
+    }
+
+    // Remaining time to live in a short human form, e.g. `1h 5m`
+    function formatTtl(ttlMs) {
+        if (ttlMs === null) {
+            return 'none';
+        }
+        if (ttlMs < 1000) {
+            return `${ttlMs}ms`;
+        }
+        let seconds = Math.floor(ttlMs / 1000);
+        const parts = [];
+        [['d', 86400], ['h', 3600], ['m', 60], ['s', 1]].forEach(([unit, size]) => {
+            if (seconds >= size && parts.length < 2) {
+                parts.push(`${Math.floor(seconds / size)}${unit}`);
+                seconds %= size;
+            }
+        });
+        return parts.join(' ');
+    }
+
     function displayKeys(paginationData) {
         const keysTable = document.getElementById('keys-table-body');
         keysTable.innerHTML = '';
 
-        paginationData.keys.forEach(([key, value]) => {
+        const isCluster = paginationData.mode === 'cluster';
+        document.querySelectorAll('th.cluster-only').forEach(th => th.style.display = isCluster ? '' : 'none');
+
+        document.getElementById('select-all').checked = false;
+        paginationData.keys.forEach(({ id, key, value, size, ttl_ms, location }) => {
             const row = document.createElement('tr');
+            const locationCell = isCluster && location ? `<td>${escapeHtml(location.node)} / ${location.slot}</td>` : '';
+            const preview = escapeHtml(formatValue(value, size));
+            const name = escapeHtml(key);
             row.innerHTML = `
-                <td class="truncated" title="${key}">${key}</td>
-                <td class="truncated" title="${value}">${value}</td>
+                <td><input type="checkbox" class="key-select" value="${id}"></td>
+                <td class="truncated" title="${name}">${name}</td>
+                <td>${value.type === 'Unknown' ? escapeHtml(value.value) : value.type}</td>
+                <td>${formatTtl(ttl_ms)}</td>
+                <td class="truncated" title="${preview}">${preview}</td>
+                ${locationCell}
                 <td>
-                    <button onclick="editKey('${key}')">Edit</button>
-                    <button onclick="deleteKey('${key}')">Delete</button>
+                    <button onclick="editKey('${id}')">Edit</button>
+                    <button onclick="deleteKey('${id}')">Delete</button>
                 </td>`;
             keysTable.appendChild(row);
         });
 
+        cursors.length = currentPage + 1;
+        if (paginationData.cursor) {
+            cursors.push(paginationData.cursor);
+        }
+
         const currentPageInputs = document.querySelectorAll('[id^="current-page"]');
-        const totalPagesSpans = document.querySelectorAll('[id^="total-pages"]');
+        currentPageInputs.forEach(input => input.value = currentPage + 1);
+        showTotalPages();
+        document.querySelectorAll('[id^="next-page"]').forEach(button => button.disabled = !paginationData.cursor);
+        document.querySelectorAll('[id^="prev-page"]').forEach(button => button.disabled = currentPage === 0);
+    }
+
+    // A count only applies to the connection, database, search and filters it was taken with
+    function countScope() {
+        return `${connectionId} ${dbSelect.value} ${currentSearchQuery()}`;
+    }
+
+    function showTotalPages() {
+        const pageSize = parseInt(pageSizeDropdowns[0].value) || defaultPageSize;
+        const scope = countScope();
+        let text = '?';
+        if (countedTotal && countedTotal.scope === scope) {
+            text = `~${Math.max(1, Math.ceil(countedTotal.total / pageSize))}`;
+        } else if (countingScope === scope) {
+            text = '…';
+        }
+        document.querySelectorAll('[id^="total-pages"]').forEach(span => span.textContent = text);
+    }
 
-        currentPageInputs.forEach(input => input.value = paginationData.current_page + 1);
-        totalPagesSpans.forEach(span => span.textContent = paginationData.total_pages);
-        currentPage = paginationData.current_page;
+    // Large keyspaces are counted in the background; ask again until the total is known, or stop
+    // once the search changed, which leaves the count to be superseded by the next one
+    async function countKeys(scope = countScope()) {
+        clearTimeout(countPoll);
+        if (scope !== countScope()) {
+            countingScope = null;
+            showTotalPages();
+            return;
+        }
+        countingScope = scope;
+        showTotalPages();
+        const response = await fetch(withConnection(`/keys/count?${currentSearchQuery()}`));
+        if (!response.ok) {
+            showAlert(`Failed to count keys: ${await readError(response)}`);
+            countingScope = null;
+            showTotalPages();
+            return;
+        }
+        const { approx_total } = await response.json();
+        if (approx_total === null) {
+            countPoll = setTimeout(() => countKeys(scope), 2000);
+            return;
+        }
+        countingScope = null;
+        countedTotal = { scope, total: approx_total };
+        showTotalPages();
     }
 
+    document.querySelectorAll('[id^="count-keys"]').forEach(button => {
+        button.addEventListener('click', () => countKeys());
+    });
+
     function updatePage(newPage) {
-        if (newPage >= 0) {
+        if (newPage >= 0 && newPage < cursors.length) {
             currentPage = newPage;
             const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
             fetchKeys(searchQuery).then(displayKeys);
@@ -87,6 +334,11 @@ document.addEventListener('DOMContentLoaded', function () {
     document.querySelectorAll('[id^="current-page"]').forEach(input => {
         input.addEventListener('change', (event) => {
             const newPage = parseInt(event.target.value) - 1;
+            if (newPage >= cursors.length) {
+                showAlert(`Only pages up to ${cursors.length} have been reached so far`);
+                event.target.value = currentPage + 1;
+                return;
+            }
             updatePage(newPage);
         });
     });
@@ -100,49 +352,297 @@ document.addEventListener('DOMContentLoaded', function () {
         });
     });
 
-    window.deleteKey = async function (key) {
-        const queryParams = new URLSearchParams({ host, port, username, password }).toString();
-        const response = await fetch(`/delete/${key}?${queryParams}`, { method: 'DELETE' });
+    const dbSelect = document.getElementById('db-select');
+
+    async function fetchDatabases() {
+        const response = await fetch(withConnection('/databases'));
+        if (!response.ok) {
+            showAlert(`Failed to fetch databases: ${await readError(response)}`);
+            return;
+        }
+        const data = await response.json();
+        dbSelect.innerHTML = '';
+        data.databases.forEach(db => {
+            const option = document.createElement('option');
+            option.value = db.index;
+            option.textContent = `db${db.index} (${db.keys} keys)`;
+            dbSelect.appendChild(option);
+        });
+        dbSelect.value = data.current;
+        updateInfoBar(data.node);
+    }
+
+    dbSelect.addEventListener('change', async () => {
+        const response = await fetch(withConnection('/databases/select'), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ db: parseInt(dbSelect.value) })
+        });
+        if (response.ok) {
+            await fetchConnection();
+            currentPage = 0;
+            reloadTree();
+            const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
+            fetchKeys(searchQuery).then(displayKeys);
+        } else {
+            const errorMessage = await readError(response);
+            showAlert(`Failed to select database: ${errorMessage}`);
+            dbSelect.value = summary ? summary.db : 0;
+        }
+    });
+
+    // Keys are addressed by their id (base64url of the name), which is safe to put in URLs as is
+    window.deleteKey = async function (id) {
+        const response = await fetch(withConnection(`/delete/${id}`), { method: 'DELETE' });
         if (response.ok) {
             showAlert('Key deleted successfully', 'success');
             const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
             fetchKeys(searchQuery).then(displayKeys);
         } else {
-            showAlert('Failed to delete key');
+            const errorMessage = await readError(response);
+            showAlert(`Failed to delete key: ${errorMessage}`);
         }
     };
 
-    window.editKey = function (key) {
-        const params = new URLSearchParams({ host, port, username, password });
-        window.location.href = `/edit-key?key=${encodeURIComponent(key)}&${params.toString()}`;
+    document.getElementById('select-all').addEventListener('change', (event) => {
+        document.querySelectorAll('.key-select').forEach(checkbox => checkbox.checked = event.target.checked);
+    });
+
+    function selectedIds() {
+        return Array.from(document.querySelectorAll('.key-select:checked'), checkbox => checkbox.value);
+    }
+
+    async function bulkExpire(expiry) {
+        const ids = selectedIds();
+        if (!ids.length) {
+            showAlert('Select at least one key');
+            return;
+        }
+        const response = await fetch(withConnection('/expire'), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ ids, expiry })
+        });
+        if (response.ok) {
+            const result = await response.json();
+            const missing = result.missing.length ? `, ${result.missing.length} no longer exist` : '';
+            showAlert(`Updated ${result.updated} keys${missing}`, 'success');
+            const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
+            fetchKeys(searchQuery).then(displayKeys);
+        } else {
+            showAlert(`Failed to update expiration: ${await readError(response)}`);
+        }
+    }
+
+    document.getElementById('bulk-expire').addEventListener('click', () => {
+        const seconds = parseInt(document.getElementById('bulk-ttl').value);
+        if (!(seconds > 0)) {
+            showAlert('Enter a TTL in seconds');
+            return;
+        }
+        bulkExpire({ ex: seconds });
+    });
+
+    document.getElementById('bulk-persist').addEventListener('click', () => bulkExpire(null));
+
+    // Sets are combined in table order, so for a difference the first selected key is the base
+    async function combineSets(path, extra = {}) {
+        const ids = selectedIds();
+        if (!ids.length) {
+            showAlert('Select at least one set');
+            return null;
+        }
+        const response = await fetch(withConnection(path), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ operation: document.getElementById('set-operation').value, ids, ...extra })
+        });
+        if (!response.ok) {
+            showAlert(`Failed to combine sets: ${await readError(response)}`);
+            return null;
+        }
+        return response.json();
+    }
+
+    document.getElementById('set-preview').addEventListener('click', async () => {
+        const result = await combineSets('/sets/combine');
+        if (result) {
+            const more = result.size > result.members.length ? ', …' : '';
+            const members = result.members.map(previewBytes).join(', ');
+            document.getElementById('set-result').textContent = `${result.size} members: ${members}${more}`;
+        }
+    });
+
+    document.getElementById('set-store').addEventListener('click', async () => {
+        const key = document.getElementById('set-destination').value;
+        if (!key) {
+            showAlert('Enter the key to store the result in');
+            return;
+        }
+        const result = await combineSets('/sets/store', { destination: { key } });
+        if (result) {
+            showAlert(`Stored ${result.size} members in ${result.key}`, 'success');
+            document.getElementById('set-destination').value = '';
+            const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
+            fetchKeys(searchQuery).then(displayKeys);
+        }
+    });
+
+    window.editKey = function (id) {
+        window.location.href = withConnection(`/edit-key?id=${id}`);
     };
 
+    const keyTree = document.getElementById('key-tree');
+
+    // Each level is fetched when its branch is first expanded
+    async function loadTreeLevel(container, prefixId = '') {
+        const delimiter = document.getElementById('tree-delimiter').value || ':';
+        const queryParams = new URLSearchParams({ prefix_id: prefixId, delimiter });
+        const response = await fetch(withConnection(`/keys/tree?${queryParams}`));
+        if (!response.ok) {
+            showAlert(`Failed to load namespaces: ${await readError(response)}`);
+            return;
+        }
+        const level = await response.json();
+        container.innerHTML = '';
+        level.branches.forEach(branch => {
+            const item = document.createElement('li');
+            const toggle = document.createElement('button');
+            toggle.type = 'button';
+            toggle.className = 'tree-toggle';
+            toggle.textContent = '▸';
+            const label = document.createElement('span');
+            label.textContent = `${branch.name}${delimiter} (${branch.keys} keys)`;
+            const children = document.createElement('ul');
+            children.className = 'key-tree';
+            children.hidden = true;
+            toggle.addEventListener('click', async () => {
+                if (children.hidden && !children.hasChildNodes()) {
+                    await loadTreeLevel(children, branch.prefix_id);
+                }
+                children.hidden = !children.hidden;
+                toggle.textContent = children.hidden ? '▸' : '▾';
+            });
+            item.append(toggle, label, children);
+            container.appendChild(item);
+        });
+        level.leaves.forEach(leaf => {
+            const item = document.createElement('li');
+            const link = document.createElement('a');
+            link.href = '#';
+            link.textContent = leaf.name || leaf.key;
+            link.title = leaf.key;
+            link.addEventListener('click', (event) => {
+                event.preventDefault();
+                editKey(leaf.id);
+            });
+            item.appendChild(link);
+            container.appendChild(item);
+        });
+        const hidden = level.branch_count - level.branches.length + level.leaf_count - level.leaves.length;
+        if (hidden > 0 || !level.complete) {
+            const note = document.createElement('li');
+            note.className = 'tree-note';
+            note.textContent = level.complete ? `${hidden} more not shown` : `Partial: only the first ${level.scanned} keys were grouped`;
+            container.appendChild(note);
+        }
+    }
+
+    function reloadTree() {
+        keyTree.innerHTML = '';
+        if (document.getElementById('tree-panel').open) {
+            loadTreeLevel(keyTree);
+        }
+    }
+
+    document.getElementById('tree-panel').addEventListener('toggle', (event) => {
+        if (event.target.open && !keyTree.hasChildNodes()) {
+            loadTreeLevel(keyTree);
+        }
+    });
+
+    document.getElementById('tree-delimiter').addEventListener('change', reloadTree);
+
     document.getElementById('create-form').addEventListener('submit', async function (event) {
         event.preventDefault();
         const newKey = document.getElementById('new-key').value;
+        const newType = document.getElementById('new-type').value;
         const newValue = document.getElementById('new-value').value;
+        const ttl = parseInt(document.getElementById('new-ttl').value);
 
-        const queryParams = new URLSearchParams({ host, port, username, password }).toString();
-        const response = await fetch(`/set?${queryParams}`, {
+        // Collections are entered as JSON, in the same shape `/get` returns them
+        let value = newValue;
+        if (newType !== 'String') {
+            try {
+                value = JSON.parse(newValue);
+            } catch (e) {
+                showAlert(`Invalid JSON value: ${e.message}`);
+                return;
+            }
+            if (newType === 'Hash' && !Array.isArray(value)) {
+                value = Object.entries(value).map(([field, fieldValue]) => ({ field, value: fieldValue }));
+            }
+            if (newType === 'Set' && Array.isArray(value)) {
+                value = { members: value };
+            }
+            // Each object becomes one entry, with an id generated by Redis
+            if (newType === 'Stream') {
+                value = { entries: value.map(fields => ({ id: '*', fields: Object.entries(fields).map(([field, fieldValue]) => ({ field, value: fieldValue })) })) };
+            }
+        }
+
+        const response = await fetch(withConnection('/set'), {
             method: 'POST',
             headers: {
                 'Content-Type': 'application/json'
             },
-            body: JSON.stringify({ key: newKey, value: newValue })
+            body: JSON.stringify({ key: newKey, type: newType, value, expiry: ttl > 0 ? { ex: ttl } : null })
         });
 
         if (response.ok) {
             showAlert('Key created successfully', 'success');
             document.getElementById('new-key').value = '';
             document.getElementById('new-value').value = '';
+            document.getElementById('new-ttl').value = '';
             const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
             fetchKeys(searchQuery).then(displayKeys);
         } else {
-            const errorMessage = await response.text();
+            const errorMessage = await readError(response);
             showAlert(`Failed to create key: ${errorMessage}`);
         }
     });
 
+    const valuePlaceholders = {
+        String: '',
+        List: '["first", "second"]',
+        Set: '["member", "other member"]',
+        ZSet: '[{"member": "alice", "score": 1.5}]',
+        Hash: '{"field": "value"}',
+        Stream: '[{"event": "login", "user": "42"}]'
+    };
+
+    document.getElementById('new-type').addEventListener('change', (event) => {
+        document.getElementById('new-value').placeholder = valuePlaceholders[event.target.value];
+    });
+
+    document.getElementById('filter-ttl').addEventListener('change', (event) => {
+        document.getElementById('filter-expires-within').style.display = event.target.value === 'within' ? '' : 'none';
+    });
+
+    ['search-mode', 'filter-type', 'filter-ttl', 'filter-expires-within', 'filter-min-size', 'filter-max-size'].forEach(id => {
+        document.getElementById(id).addEventListener('change', () => {
+            currentPage = 0;
+            const searchQuery = document.getElementById('search-input-top').value || document.getElementById('search-input-bottom').value;
+            fetchKeys(searchQuery).then(displayKeys);
+        });
+    });
+
     document.getElementById('search-input-top').addEventListener('input', (event) => {
         currentPage = 0;
         fetchKeys(event.target.value).then(displayKeys);
@@ -153,5 +653,9 @@ document.addEventListener('DOMContentLoaded', function () {
         fetchKeys(event.target.value).then(displayKeys);
     });
 
-    fetchKeys().then(displayKeys);
+    fetchConnection()
+        .then(fetchConnections)
+        .then(fetchDatabases)
+        .then(() => fetchKeys())
+        .then(displayKeys);
 });
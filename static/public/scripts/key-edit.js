@@ -1,52 +1,922 @@
 document.addEventListener('DOMContentLoaded', function () {
     const infoBar = document.getElementById('info-bar');
 
-    const host = localStorage.getItem('redis_host');
-    const port = localStorage.getItem('redis_port');
-    const username = localStorage.getItem('redis_user');
-    const password = localStorage.getItem('redis_password');
+    const params = new URLSearchParams(window.location.search);
+    const keyId = params.get('id');
+    const connectionId = params.get('connection');
 
-    if (host && port) {
-        infoBar.textContent = `Connected to ${host}:${port} as ${username ? username : 'anonymous'}`;
-    } else {
-        infoBar.textContent = `Not connected`;
+    function withConnection(path) {
+        if (!connectionId) {
+            return path;
+        }
+        const separator = path.includes('?') ? '&' : '?';
+        return `${path}${separator}connection=${encodeURIComponent(connectionId)}`;
     }
 
+    fetch(withConnection('/connection'))
+        .then(response => {
+            if (response.status === 401) {
+                window.location.href = '/';
+                return null;
+            }
+            return response.ok ? response.json() : null;
+        })
+        .then(summary => {
+            if (summary) {
+                infoBar.textContent = `${summary.name}: connected to ${summary.address}/${summary.db}${summary.tls ? ' (TLS)' : ''} as ${summary.username || 'anonymous'}`;
+            } else {
+                infoBar.textContent = `Not connected`;
+            }
+        });
+
     function showAlert(message, type = 'error') {
         const alertDiv = document.createElement('div');
         alertDiv.className = `alert ${type}`;
-        alertDiv.innerHTML = `${message}<span class="closebtn" onclick="this.parentElement.style.display='none';">&times;</span>`;
+        // Messages carry key names, member bytes and Redis errors, so they are set as text, never as HTML
+        alertDiv.textContent = message;
+        const closeButton = document.createElement('span');
+        closeButton.className = 'closebtn';
+        closeButton.textContent = '\u00d7';
+        closeButton.addEventListener('click', () => alertDiv.style.display = 'none');
+        alertDiv.appendChild(closeButton);
         document.body.prepend(alertDiv);
         setTimeout(() => alertDiv.style.display = 'none', 3000);
     }
 
-    const params = new URLSearchParams(window.location.search);
-    const key = params.get('key');
+    // Errors come back as a JSON envelope ({ code, message, redis_kind, retryable })
+    async function readError(response) {
+        const body = await response.text();
+        try {
+            return JSON.parse(body).message || body;
+        } catch (e) {
+            return body;
+        }
+    }
+
+    // Shows the key name behind an id, escaping bytes that are not valid UTF-8 as `\xNN`
+    function keyName(id) {
+        const binary = atob(id.replace(/-/g, '+').replace(/_/g, '/'));
+        const bytes = Uint8Array.from(binary, c => c.charCodeAt(0));
+        try {
+            return new TextDecoder('utf-8', { fatal: true }).decode(bytes);
+        } catch (e) {
+            return Array.from(bytes, b => b >= 0x20 && b < 0x7f && b !== 0x5c ? String.fromCharCode(b) : `\\x${b.toString(16).padStart(2, '0')}`).join('');
+        }
+    }
+
+    document.getElementById('key').value = keyName(keyId);
+
+    let valueType = 'String';
+    const valueInput = document.getElementById('value');
+    const encodingSelect = document.getElementById('encoding');
+    let currentEncoding = 'text';
+
+    function bytesFromView(text, encoding) {
+        switch (encoding) {
+            case 'base64':
+                return Uint8Array.from(atob(text.replace(/\s/g, '')), c => c.charCodeAt(0));
+            case 'hex': {
+                const digits = text.replace(/\s/g, '');
+                if (!/^([0-9a-fA-F]{2})*$/.test(digits)) {
+                    throw new Error('invalid hex');
+                }
+                return Uint8Array.from(digits.match(/../g) || [], pair => parseInt(pair, 16));
+            }
+            default:
+                return new TextEncoder().encode(text);
+        }
+    }
+
+    function bytesToView(bytes, encoding) {
+        switch (encoding) {
+            case 'base64':
+                return btoa(Array.from(bytes, b => String.fromCharCode(b)).join(''));
+            case 'hex':
+                return Array.from(bytes, b => b.toString(16).padStart(2, '0')).join('');
+            default:
+                // Throws for binary data, which cannot be edited as text
+                return new TextDecoder('utf-8', { fatal: true }).decode(bytes);
+        }
+    }
+
+    // Elements are edited as plain strings, or as `{"base64": ...}` when they are not valid UTF-8
+    function editableBytes(bytes) {
+        return bytes.encoding === 'utf8' ? bytes.text : { base64: bytes.base64 };
+    }
+
+    // Collections are edited as JSON, in the same shape `/get` returns and `/set` accepts;
+    // hashes with text field names are shown as a plain object for readability
+    function toEditText(value) {
+        switch (value.type) {
+            case 'List':
+                return JSON.stringify(value.value.map(editableBytes), null, 2);
+            case 'Set':
+                return JSON.stringify(value.value.members.map(editableBytes), null, 2);
+            case 'ZSet':
+                return JSON.stringify(value.value.map(({ member, score }) => ({ member: editableBytes(member), score })), null, 2);
+            case 'Hash':
+                if (value.value.every(({ field }) => field.encoding === 'utf8')) {
+                    return JSON.stringify(Object.fromEntries(value.value.map(({ field, value }) => [field.text, editableBytes(value)])), null, 2);
+                }
+                return JSON.stringify(value.value.map(({ field, value }) => ({ field: editableBytes(field), value: editableBytes(value) })), null, 2);
+            default:
+                return '';
+        }
+    }
+
+    encodingSelect.addEventListener('change', () => {
+        try {
+            valueInput.value = bytesToView(bytesFromView(valueInput.value, currentEncoding), encodingSelect.value);
+            currentEncoding = encodingSelect.value;
+        } catch (e) {
+            showAlert(`Cannot show the value as ${encodingSelect.value}: ${e.message}`);
+            encodingSelect.value = currentEncoding;
+        }
+    });
+
+    fetch(withConnection(`/get/${keyId}`))
+        .then(async response => {
+            if (!response.ok) {
+                const errorMessage = await readError(response);
+                showAlert(`Failed to load key: ${errorMessage}`);
+                return;
+            }
+            const value = await response.json();
+            valueType = value.type;
+            document.getElementById('type').value = value.type === 'Unknown' ? value.value : value.type;
+            if (value.type === 'List') {
+                document.getElementById('value-field').style.display = 'none';
+                document.getElementById('list-panel').style.display = '';
+                loadListElements(0);
+            } else if (value.type === 'Set') {
+                document.getElementById('value-field').style.display = 'none';
+                document.getElementById('set-panel').style.display = '';
+                loadSetMembers();
+            } else if (value.type === 'ZSet') {
+                document.getElementById('value-field').style.display = 'none';
+                document.getElementById('zset-panel').style.display = '';
+                loadZSetMembers();
+            } else if (value.type === 'Stream') {
+                document.getElementById('value-field').style.display = 'none';
+                document.getElementById('stream-panel').style.display = '';
+                loadStreamEntries();
+                loadStreamInfo();
+            } else if (value.type === 'String') {
+                document.getElementById('encoding-field').style.display = '';
+                currentEncoding = value.value.encoding === 'utf8' ? 'text' : 'base64';
+                encodingSelect.value = currentEncoding;
+                valueInput.value = currentEncoding === 'text' ? value.value.text : value.value.base64;
+            } else {
+                valueInput.value = toEditText(value);
+            }
+            valueInput.readOnly = value.type === 'Unknown';
+            showTtl(value.ttl_ms);
+        });
+
+    // Lists are edited element by element through the list endpoints instead of being saved as a whole
+    const listPath = `/lists/${keyId}`;
+    const listPageSize = 50;
+    let listStart = 0;
+
+    async function loadListElements(start = listStart) {
+        const queryParams = new URLSearchParams({ start, count: listPageSize });
+        const response = await fetch(withConnection(`${listPath}/elements?${queryParams}`));
+        if (response.status === 404) {
+            // Popping, removing or trimming the last element deletes the list
+            document.getElementById('list-elements').innerHTML = '';
+            document.getElementById('list-summary').textContent = 'The list is empty and no longer exists';
+            return;
+        }
+        if (!response.ok) {
+            showAlert(`Failed to load elements: ${await readError(response)}`);
+            return;
+        }
+        const page = await response.json();
+        listStart = page.start;
+        document.getElementById('list-start').value = page.start;
+        document.getElementById('list-prev').disabled = page.start === 0;
+        document.getElementById('list-next').disabled = page.next === null;
+        document.getElementById('list-summary').textContent = page.elements.length
+            ? `${page.start}-${page.start + page.elements.length - 1} of ${page.length}`
+            : `none of ${page.length}`;
+        const body = document.getElementById('list-elements');
+        body.innerHTML = '';
+        page.elements.forEach((element, offset) => {
+            const index = page.start + offset;
+            const actions = document.createElement('span');
+            actions.append(
+                actionButton('Edit', () => setListElement(index, element)),
+                actionButton('Insert after', () => insertListElement(element))
+            );
+            body.appendChild(tableRow([index, previewBytes(element), actions]));
+        });
+    }
+
+    // Asks for a new element, as text or, to keep binary data intact, as hex
+    function promptBytes(message, current = null) {
+        const binary = current !== null && current.encoding !== 'utf8';
+        const input = window.prompt(binary ? `${message} (hex):` : `${message}:`, current === null ? '' : binary ? current.hex : current.text);
+        if (input === null) {
+            return null;
+        }
+        return binary ? { hex: input.replace(/\s/g, '') } : input;
+    }
+
+    async function setListElement(index, element) {
+        const value = promptBytes(`New value of element ${index}`, element);
+        if (value === null) {
+            return;
+        }
+        const response = await postJson(`${listPath}/set`, { index, value });
+        if (response.ok) {
+            showAlert(`Element ${index} saved successfully`, 'success');
+            loadListElements();
+        } else {
+            showAlert(`Failed to set element: ${await readError(response)}`);
+        }
+    }
+
+    // LINSERT places the value after the first element equal to the pivot
+    async function insertListElement(pivot) {
+        const value = promptBytes(`Value to insert after "${previewBytes(pivot)}"`);
+        if (value === null) {
+            return;
+        }
+        const response = await postJson(`${listPath}/insert`, { position: 'after', pivot: editableBytes(pivot), value });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Element inserted, the list now has ${result.length} elements`, 'success');
+            loadListElements();
+        } else {
+            showAlert(`Failed to insert element: ${await readError(response)}`);
+        }
+    }
+
+    document.getElementById('list-go').addEventListener('click', () => loadListElements(parseInt(document.getElementById('list-start').value) || 0));
+    document.getElementById('list-prev').addEventListener('click', () => loadListElements(Math.max(listStart - listPageSize, 0)));
+    document.getElementById('list-next').addEventListener('click', () => loadListElements(listStart + listPageSize));
+
+    document.getElementById('list-push-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const end = document.getElementById('list-push-end').value;
+        const response = await postJson(`${listPath}/push`, { end, values: [document.getElementById('list-push-value').value] });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Element pushed, the list now has ${result.length} elements`, 'success');
+            document.getElementById('list-push-value').value = '';
+            loadListElements();
+        } else {
+            showAlert(`Failed to push element: ${await readError(response)}`);
+        }
+    });
 
-    document.getElementById('key').value = key;
+    document.getElementById('list-pop-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const count = parseInt(document.getElementById('list-pop-count').value) || 1;
+        const response = await postJson(`${listPath}/pop`, { end: document.getElementById('list-pop-end').value, count });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Popped ${result.elements.map(previewBytes).join(', ')}`, 'success');
+            loadListElements();
+        } else {
+            showAlert(`Failed to pop elements: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('list-remove-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const count = parseInt(document.getElementById('list-remove-count').value) || 0;
+        const response = await postJson(`${listPath}/remove`, { value: document.getElementById('list-remove-value').value, count });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Removed ${result.removed} elements`, 'success');
+            loadListElements();
+        } else {
+            showAlert(`Failed to remove elements: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('list-trim-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const start = parseInt(document.getElementById('list-trim-start').value);
+        const stop = parseInt(document.getElementById('list-trim-stop').value);
+        const response = await postJson(`${listPath}/trim`, { start, stop });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`List trimmed to ${result.length} elements`, 'success');
+            loadListElements(0);
+        } else {
+            showAlert(`Failed to trim list: ${await readError(response)}`);
+        }
+    });
+
+    // Sets are walked with SSCAN and changed member by member instead of being saved as a whole
+    const setPath = `/sets/${keyId}`;
+    const setPageSize = 50;
+    let setNext = null;
+
+    async function loadSetMembers(cursor = null) {
+        const queryParams = new URLSearchParams({ count: setPageSize });
+        const pattern = document.getElementById('set-match').value;
+        if (pattern) {
+            queryParams.set('match', pattern);
+        }
+        if (cursor) {
+            queryParams.set('cursor', cursor);
+        }
+        const response = await fetch(withConnection(`${setPath}/members?${queryParams}`));
+        if (response.status === 404) {
+            // Removing the last member deletes the set
+            document.getElementById('set-members').innerHTML = '';
+            document.getElementById('set-summary').textContent = 'The set is empty and no longer exists';
+            return;
+        }
+        if (!response.ok) {
+            showAlert(`Failed to load members: ${await readError(response)}`);
+            return;
+        }
+        const page = await response.json();
+        setNext = page.next;
+        document.getElementById('set-next').disabled = !setNext;
+        document.getElementById('set-summary').textContent = `${page.size} members in total`;
+        const body = document.getElementById('set-members');
+        body.innerHTML = '';
+        page.members.forEach(member => {
+            body.appendChild(tableRow([previewBytes(member), actionButton('Remove', () => removeSetMember(member))]));
+        });
+    }
+
+    async function removeSetMember(member) {
+        const response = await postJson(`${setPath}/remove`, { members: [editableBytes(member)] });
+        if (response.ok) {
+            showAlert(`Member ${previewBytes(member)} removed`, 'success');
+            loadSetMembers();
+        } else {
+            showAlert(`Failed to remove member: ${await readError(response)}`);
+        }
+    }
+
+    document.getElementById('set-first').addEventListener('click', () => loadSetMembers());
+    document.getElementById('set-next').addEventListener('click', () => loadSetMembers(setNext));
+    document.getElementById('set-match').addEventListener('keydown', (event) => {
+        if (event.key === 'Enter') {
+            loadSetMembers();
+        }
+    });
+
+    document.getElementById('set-add-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const member = document.getElementById('set-add-member').value;
+        const response = await postJson(`${setPath}/add`, { members: [member] });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(result.added ? `Member ${member} added` : `${member} is already a member`, 'success');
+            document.getElementById('set-add-member').value = '';
+            loadSetMembers();
+        } else {
+            showAlert(`Failed to add member: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('set-check-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const member = document.getElementById('set-check-member').value;
+        const response = await postJson(`${setPath}/contains`, { member });
+        if (response.ok) {
+            const result = await response.json();
+            document.getElementById('set-check-result').textContent = result.contains ? `${member} is a member` : `${member} is not a member`;
+        } else {
+            showAlert(`Failed to check member: ${await readError(response)}`);
+        }
+    });
 
-    fetch(`/get/${key}?host=${host}&port=${port}&username=${username}&password=${password}`)
-        .then(response => response.text())
-        .then(value => {
-            document.getElementById('value').value = value;
+    // Sorted sets are browsed by rank, score or lex range and changed member by member
+    const zsetPath = `/zsets/${keyId}`;
+    const zsetPageSize = 50;
+    let zsetNext = null;
+
+    // Range fields shared by paging and ZRANGESTORE; empty bounds cover the whole set
+    function zsetRange() {
+        const range = { by: document.getElementById('zset-by').value, reverse: document.getElementById('zset-reverse').checked };
+        ['min', 'max'].forEach(bound => {
+            const value = document.getElementById(`zset-${bound}`).value.trim();
+            if (value) {
+                range[bound] = value;
+            }
+        });
+        return range;
+    }
+
+    async function loadZSetMembers(offset = 0) {
+        const queryParams = new URLSearchParams({ ...zsetRange(), offset, count: zsetPageSize });
+        const response = await fetch(withConnection(`${zsetPath}/members?${queryParams}`));
+        if (response.status === 404) {
+            // Removing the last member deletes the sorted set
+            document.getElementById('zset-members').innerHTML = '';
+            document.getElementById('zset-summary').textContent = 'The sorted set is empty and no longer exists';
+            return;
+        }
+        if (!response.ok) {
+            showAlert(`Failed to load members: ${await readError(response)}`);
+            return;
+        }
+        const page = await response.json();
+        zsetNext = page.next;
+        document.getElementById('zset-next').disabled = zsetNext === null;
+        document.getElementById('zset-summary').textContent = `${page.size} members in total`;
+        const body = document.getElementById('zset-members');
+        body.innerHTML = '';
+        page.members.forEach(({ member, score }) => {
+            const actions = document.createElement('span');
+            actions.append(
+                actionButton('Increment', () => incrementZSetMember(member)),
+                actionButton('Remove', () => removeZSetMember(member))
+            );
+            body.appendChild(tableRow([previewBytes(member), score, actions]));
         });
+    }
+
+    async function incrementZSetMember(member) {
+        const increment = parseFloat(window.prompt(`Add to the score of ${previewBytes(member)} (negative to decrease):`, '1'));
+        if (Number.isNaN(increment)) {
+            return;
+        }
+        const response = await postJson(`${zsetPath}/incr`, { member: editableBytes(member), increment });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`${previewBytes(member)} now scores ${result.score}`, 'success');
+            loadZSetMembers();
+        } else {
+            showAlert(`Failed to increment score: ${await readError(response)}`);
+        }
+    }
+
+    async function removeZSetMember(member) {
+        const response = await postJson(`${zsetPath}/remove`, { members: [editableBytes(member)] });
+        if (response.ok) {
+            showAlert(`Member ${previewBytes(member)} removed`, 'success');
+            loadZSetMembers();
+        } else {
+            showAlert(`Failed to remove member: ${await readError(response)}`);
+        }
+    }
+
+    document.getElementById('zset-by').addEventListener('change', () => {
+        const placeholders = { rank: ['0', '-1'], score: ['-inf', '+inf'], lex: ['-', '+'] }[document.getElementById('zset-by').value];
+        document.getElementById('zset-min').placeholder = placeholders[0];
+        document.getElementById('zset-max').placeholder = placeholders[1];
+        loadZSetMembers();
+    });
+    document.getElementById('zset-reverse').addEventListener('change', () => loadZSetMembers());
+    document.getElementById('zset-first').addEventListener('click', () => loadZSetMembers());
+    document.getElementById('zset-next').addEventListener('click', () => loadZSetMembers(zsetNext));
+
+    document.getElementById('zset-add-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const member = document.getElementById('zset-add-member').value;
+        const score = parseFloat(document.getElementById('zset-add-score').value);
+        const condition = document.getElementById('zset-add-condition').value;
+        const body = { members: [{ member, score }] };
+        if (condition) {
+            body[condition] = true;
+        }
+        const response = await postJson(`${zsetPath}/add`, body);
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(result.changed ? `Member ${member} saved` : `Member ${member} left unchanged`, 'success');
+            document.getElementById('zset-add-member').value = '';
+            document.getElementById('zset-add-score').value = '';
+            loadZSetMembers();
+        } else {
+            showAlert(`Failed to save member: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('zset-store-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const key = document.getElementById('zset-destination').value;
+        const response = await postJson(`${zsetPath}/store`, { ...zsetRange(), destination: { key } });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Stored ${result.size} members in ${result.key}`, 'success');
+            document.getElementById('zset-destination').value = '';
+        } else {
+            showAlert(`Failed to store range: ${await readError(response)}`);
+        }
+    });
+
+    // Streams are browsed page by page through the stream endpoints instead of being saved as a whole
+    const streamPath = `/streams/${keyId}`;
+    const streamPageSize = 20;
+    let streamNext = null;
+
+    // Binary data is previewed as hex, valid UTF-8 as text
+    function previewBytes(bytes) {
+        return bytes.encoding === 'utf8' ? bytes.text : `0x${bytes.hex}`;
+    }
+
+    function tableRow(cells) {
+        const row = document.createElement('tr');
+        cells.forEach(cell => {
+            const td = document.createElement('td');
+            if (cell instanceof Node) {
+                td.appendChild(cell);
+            } else {
+                td.textContent = cell;
+            }
+            row.appendChild(td);
+        });
+        return row;
+    }
+
+    function actionButton(label, onClick) {
+        const button = document.createElement('button');
+        button.type = 'button';
+        button.textContent = label;
+        button.addEventListener('click', onClick);
+        return button;
+    }
+
+    async function loadStreamEntries(from = null) {
+        const reverse = document.getElementById('stream-order').value === 'newest';
+        const queryParams = new URLSearchParams({ count: streamPageSize, reverse });
+        if (from) {
+            queryParams.set(reverse ? 'end' : 'start', from);
+        }
+        const response = await fetch(withConnection(`${streamPath}/entries?${queryParams}`));
+        if (!response.ok) {
+            showAlert(`Failed to load entries: ${await readError(response)}`);
+            return;
+        }
+        const page = await response.json();
+        streamNext = page.next;
+        document.getElementById('stream-next').disabled = !streamNext;
+        const body = document.getElementById('stream-entries');
+        body.innerHTML = '';
+        page.entries.forEach(entry => {
+            const fields = JSON.stringify(Object.fromEntries(entry.fields.map(({ field, value }) => [previewBytes(field), previewBytes(value)])));
+            body.appendChild(tableRow([entry.id, fields, actionButton('Delete', () => deleteStreamEntry(entry.id))]));
+        });
+    }
+
+    async function deleteStreamEntry(id) {
+        const response = await fetch(withConnection(`${streamPath}/entries/delete`), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ ids: [id] })
+        });
+        if (response.ok) {
+            showAlert('Entry deleted successfully', 'success');
+            loadStreamEntries();
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to delete entry: ${await readError(response)}`);
+        }
+    }
+
+    async function loadStreamInfo() {
+        const response = await fetch(withConnection(`${streamPath}/info`));
+        if (!response.ok) {
+            showAlert(`Failed to load stream info: ${await readError(response)}`);
+            return;
+        }
+        const info = await response.json();
+        document.getElementById('stream-summary').textContent = info.length
+            ? `${info.length} entries, from ${info.first_entry_id} to ${info.last_entry_id} (last generated ${info.last_generated_id})`
+            : 'The stream is empty';
+        const body = document.getElementById('stream-groups');
+        body.innerHTML = '';
+        info.groups.forEach(group => {
+            const actions = document.createElement('span');
+            actions.append(
+                actionButton('Consumers', () => showConsumers(group.name)),
+                actionButton('Pending', () => showPending(group.name)),
+                actionButton('Set ID', () => setGroupId(group.name)),
+                actionButton('Destroy', () => destroyGroup(group.name))
+            );
+            body.appendChild(tableRow([group.name, group.consumers, group.pending, group.last_delivered_id || '', group.lag ?? 'n/a', actions]));
+        });
+    }
+
+    function showGroupDetails(title, headers, rows, more = null) {
+        const details = document.getElementById('group-details');
+        details.innerHTML = '';
+        const heading = document.createElement('h3');
+        heading.textContent = title;
+        const table = document.createElement('table');
+        const head = document.createElement('thead');
+        head.appendChild(tableRow(headers));
+        const body = document.createElement('tbody');
+        rows.forEach(row => body.appendChild(tableRow(row)));
+        table.append(head, body);
+        details.append(heading, table);
+        if (more) {
+            details.appendChild(more);
+        }
+    }
+
+    function groupPath(group) {
+        return `${streamPath}/groups/${encodeURIComponent(group)}`;
+    }
+
+    async function postJson(path, body) {
+        return fetch(withConnection(path), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify(body)
+        });
+    }
+
+    async function showConsumers(group) {
+        const response = await fetch(withConnection(`${groupPath(group)}/consumers`));
+        if (!response.ok) {
+            showAlert(`Failed to load consumers: ${await readError(response)}`);
+            return;
+        }
+        const consumers = await response.json();
+        showGroupDetails(
+            `Consumers of ${group}`,
+            ['Consumer', 'Pending', 'Idle (ms)', 'Inactive (ms)', 'Actions'],
+            consumers.map(consumer => {
+                const actions = document.createElement('span');
+                actions.append(
+                    actionButton('Pending', () => showPending(group, null, consumer.name)),
+                    actionButton('Delete', () => deleteConsumer(group, consumer.name))
+                );
+                return [consumer.name, consumer.pending, consumer.idle_ms, consumer.inactive_ms ?? 'n/a', actions];
+            })
+        );
+    }
+
+    // Claim controls shared by the per entry "Claim" buttons and "Auto-claim"
+    function claimControls(group) {
+        const controls = document.createElement('div');
+        controls.className = 'inline-form';
+        controls.innerHTML = `
+            <label for="claim-consumer">Claim for consumer:</label>
+            <input type="text" id="claim-consumer">
+            <label for="claim-min-idle">Min idle (ms):</label>
+            <input type="number" id="claim-min-idle" min="0" value="0">`;
+        controls.appendChild(actionButton('Auto-claim', () => autoclaimEntries(group)));
+        return controls;
+    }
+
+    function claimTarget() {
+        const consumer = document.getElementById('claim-consumer').value.trim();
+        if (!consumer) {
+            showAlert('Enter the consumer to claim the entries for');
+            return null;
+        }
+        return { consumer, min_idle_ms: parseInt(document.getElementById('claim-min-idle').value) || 0 };
+    }
+
+    async function showPending(group, from = null, consumer = null) {
+        const queryParams = new URLSearchParams({ count: streamPageSize });
+        if (from) {
+            queryParams.set('start', from);
+        }
+        if (consumer) {
+            queryParams.set('consumer', consumer);
+        }
+        const response = await fetch(withConnection(`${groupPath(group)}/pending?${queryParams}`));
+        if (!response.ok) {
+            showAlert(`Failed to load pending entries: ${await readError(response)}`);
+            return;
+        }
+        const pending = await response.json();
+        const perConsumer = pending.consumers.map(({ name, pending }) => `${name}: ${pending}`).join(', ');
+        const more = document.createElement('div');
+        more.appendChild(claimControls(group));
+        if (pending.next) {
+            more.appendChild(actionButton('Next page', () => showPending(group, pending.next, consumer)));
+        }
+        showGroupDetails(
+            `Pending entries of ${group}${consumer ? ` delivered to ${consumer}` : ''} (${pending.count} total${perConsumer ? `; ${perConsumer}` : ''})`,
+            ['ID', 'Consumer', 'Idle (ms)', 'Deliveries', 'Actions'],
+            pending.entries.map(entry => [entry.id, entry.consumer, entry.idle_ms, entry.deliveries, actionButton('Claim', () => claimEntry(group, entry.id))]),
+            more
+        );
+    }
+
+    async function claimEntry(group, id) {
+        const target = claimTarget();
+        if (!target) {
+            return;
+        }
+        const response = await postJson(`${groupPath(group)}/claim`, { ...target, ids: [id] });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(result.claimed.length ? `Entry ${id} claimed by ${target.consumer}` : `Entry ${id} was not idle long enough`, 'success');
+            showPending(group);
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to claim entry: ${await readError(response)}`);
+        }
+    }
+
+    async function autoclaimEntries(group) {
+        const target = claimTarget();
+        if (!target) {
+            return;
+        }
+        const response = await postJson(`${groupPath(group)}/autoclaim`, { ...target, count: streamPageSize });
+        if (response.ok) {
+            const result = await response.json();
+            const more = result.next ? ', run again for more' : '';
+            showAlert(`Claimed ${result.claimed.length} entries for ${target.consumer}${more}`, 'success');
+            showPending(group);
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to auto-claim entries: ${await readError(response)}`);
+        }
+    }
+
+    async function deleteConsumer(group, consumer) {
+        const response = await fetch(withConnection(`${groupPath(group)}/consumers/${encodeURIComponent(consumer)}`), { method: 'DELETE' });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Consumer ${consumer} deleted, ${result.pending_removed} pending entries dropped`, 'success');
+            showConsumers(group);
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to delete consumer: ${await readError(response)}`);
+        }
+    }
+
+    async function setGroupId(group) {
+        const id = window.prompt(`New last delivered id of ${group} ($ for the end of the stream, 0 to replay everything):`);
+        if (!id) {
+            return;
+        }
+        const response = await postJson(`${groupPath(group)}/setid`, { id });
+        if (response.ok) {
+            showAlert(`Group ${group} now starts after ${id}`, 'success');
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to set group id: ${await readError(response)}`);
+        }
+    }
+
+    async function destroyGroup(group) {
+        if (!window.confirm(`Destroy group ${group} with all its consumers and pending entries?`)) {
+            return;
+        }
+        const response = await fetch(withConnection(groupPath(group)), { method: 'DELETE' });
+        if (response.ok) {
+            showAlert(`Group ${group} destroyed`, 'success');
+            document.getElementById('group-details').innerHTML = '';
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to destroy group: ${await readError(response)}`);
+        }
+    }
+
+    document.getElementById('group-create-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const group = document.getElementById('group-name').value.trim();
+        const response = await postJson(`${streamPath}/groups`, { group, id: document.getElementById('group-start').value });
+        if (response.ok) {
+            showAlert(`Group ${group} created`, 'success');
+            document.getElementById('group-name').value = '';
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to create group: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('stream-order').addEventListener('change', () => loadStreamEntries());
+    document.getElementById('stream-first').addEventListener('click', () => loadStreamEntries());
+    document.getElementById('stream-next').addEventListener('click', () => loadStreamEntries(streamNext));
+
+    document.getElementById('stream-add-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        let fields;
+        try {
+            fields = Object.entries(JSON.parse(document.getElementById('stream-entry-fields').value));
+        } catch (e) {
+            showAlert(`Invalid JSON fields: ${e.message}`);
+            return;
+        }
+        const id = document.getElementById('stream-entry-id').value.trim();
+        const response = await fetch(withConnection(`${streamPath}/entries`), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ id: id || null, fields: fields.map(([field, value]) => ({ field, value: String(value) })) })
+        });
+        if (response.ok) {
+            const added = await response.json();
+            showAlert(`Entry ${added.id} added successfully`, 'success');
+            document.getElementById('stream-entry-id').value = '';
+            document.getElementById('stream-entry-fields').value = '';
+            loadStreamEntries();
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to add entry: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('stream-trim-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const maxlen = parseInt(document.getElementById('stream-maxlen').value);
+        const response = await fetch(withConnection(`${streamPath}/trim`), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ maxlen, approximate: document.getElementById('stream-approximate').checked })
+        });
+        if (response.ok) {
+            const result = await response.json();
+            showAlert(`Removed ${result.removed} entries`, 'success');
+            loadStreamEntries();
+            loadStreamInfo();
+        } else {
+            showAlert(`Failed to trim stream: ${await readError(response)}`);
+        }
+    });
+
+    function showTtl(ttlMs) {
+        document.getElementById('ttl').value = ttlMs === null ? 'none' : `${Math.ceil(ttlMs / 1000)}s`;
+    }
+
+    async function refreshTtl() {
+        const response = await fetch(withConnection(`/get/${keyId}`));
+        if (response.ok) {
+            showTtl((await response.json()).ttl_ms);
+        }
+    }
+
+    document.getElementById('ttl-form').addEventListener('submit', async function (event) {
+        event.preventDefault();
+        const seconds = parseInt(document.getElementById('new-ttl').value);
+        const response = await fetch(withConnection(`/expire/${keyId}`), {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json'
+            },
+            body: JSON.stringify({ ex: seconds })
+        });
+        if (response.ok) {
+            showAlert('Expiration set successfully', 'success');
+            document.getElementById('new-ttl').value = '';
+            refreshTtl();
+        } else {
+            showAlert(`Failed to set expiration: ${await readError(response)}`);
+        }
+    });
+
+    document.getElementById('persist').addEventListener('click', async () => {
+        const response = await fetch(withConnection(`/persist/${keyId}`), { method: 'POST' });
+        if (response.ok) {
+            showAlert('Expiration removed successfully', 'success');
+            refreshTtl();
+        } else {
+            showAlert(`Failed to remove expiration: ${await readError(response)}`);
+        }
+    });
 
     document.getElementById('edit-form').addEventListener('submit', function (event) {
         event.preventDefault();
-        const value = document.getElementById('value').value;
+        let value = valueInput.value;
+        if (valueType === 'String') {
+            if (currentEncoding !== 'text') {
+                value = { [currentEncoding]: value.replace(/\s/g, '') };
+            }
+        } else {
+            try {
+                value = JSON.parse(value);
+            } catch (e) {
+                showAlert(`Invalid JSON value: ${e.message}`);
+                return;
+            }
+            if (valueType === 'Hash' && !Array.isArray(value)) {
+                value = Object.entries(value).map(([field, fieldValue]) => ({ field, value: fieldValue }));
+            }
+            if (valueType === 'Set' && Array.isArray(value)) {
+                value = { members: value };
+            }
+        }
 
-        fetch(`/set?host=${host}&port=${port}&username=${username}&password=${password}`, {
+        fetch(withConnection('/set'), {
             method: 'POST',
             headers: {
                 'Content-Type': 'application/json'
             },
-            body: JSON.stringify({ key, value })
+            // Saving a new value keeps the key's current expiration
+            body: JSON.stringify({ id: keyId, type: valueType, value, expiry: 'keep_ttl' })
         })
-        .then(response => {
+        .then(async response => {
             if (response.ok) {
                 showAlert('Key saved successfully', 'success');
             } else {
-                showAlert('Failed to save key');
+                const errorMessage = await readError(response);
+                showAlert(`Failed to save key: ${errorMessage}`);
             }
         });
     });